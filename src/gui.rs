@@ -1,17 +1,43 @@
-use iced::advanced::Widget;
+use calendar::CalendarSpan;
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, TimeZone};
+use command_palette::{PaletteCommand, PaletteItem};
+use estimate::{EffortTotals, Estimate};
+use focus::{Focus, FocusPhase};
+use iced::advanced::widget::{self, operation::focusable};
+use iced::keyboard::{self, KeyCode, Modifiers};
+use iced::widget::scrollable::{self, RelativeOffset};
 use iced::widget::{
-    button, checkbox, column, container, pick_list, row, scrollable, text, text_input,
-    vertical_space, Button, Checkbox, Column, Container, PickList, Row, Scrollable, Text,
-    TextInput,
+    button, checkbox, column, container, mouse_area, pick_list, row, text, text_input, tooltip,
+    Button, Checkbox, Column, Container, Row, Scrollable, Text, TextInput,
 };
-use iced::{alignment, executor, font, widget, window, Subscription};
-use iced::{Alignment, Application, Command, Element, Length, Theme};
-use iced_aw::style::card::CardStyles;
-use iced_aw::{card, modal, Modal};
+use iced::{alignment, event, executor, font, mouse, subscription, window, Event, Subscription};
+use iced::{Alignment, Application, Color, Command, Element, Length, Theme};
+use iced_aw::graphics::icons::{Icon, ICON_FONT};
+use iced_aw::{card, modal};
+use json_handling::{
+    DateFormat, ListInfo, Settings, SortOrder, StartupBehavior, WeekStart, WindowGeometry,
+};
+use keymap::Action;
+use line_input::LineInput;
+use reminders::ReminderAction;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use time_tracking::{ReportGroup, TimeSession};
+use ui_theme::ThemeDefinition;
 
+mod calendar;
+mod command_palette;
+mod estimate;
+mod focus;
+mod history;
 pub(crate) mod json_handling;
+mod keymap;
+mod line_input;
+mod markdown;
+mod reminders;
+mod time_tracking;
 mod ui_theme;
+pub(crate) mod window_state;
 
 #[derive(Debug)]
 enum State {
@@ -19,6 +45,15 @@ enum State {
     Edit,
     NewFile,
     DeleteList,
+    DeleteTask,
+    Settings,
+    CommandPalette,
+    Bulk,
+    Transfer,
+    ManageList,
+    ListDetails,
+    History,
+    TimeReport,
     None,
 }
 
@@ -28,58 +63,693 @@ enum ButtonPressed {
     Edit,
     NewFile,
     DeleteList,
+    DeleteTask,
+    Settings,
+    CommandPalette,
+    Bulk,
+    Transfer,
+    ManageList,
+    ListDetails,
+    History,
+    TimeReport,
 }
 
+/// The next free id in the current list. Ids are taken as one past the
+/// highest stored id, the file order follows the manual positions and says
+/// nothing about which ids are in use.
 fn get_id(mem: &mut Mem) -> i32 {
-    let tasks = json_handling::read_tasks(mem.list_name.clone()).unwrap_or(Tasks::default());
-    let mut id = tasks.tasks.last().unwrap_or(&TaskEntry::default()).id;
+    mem.id = json_handling::read_tasks(mem.list_name.clone())
+        .unwrap_or_default()
+        .next_id();
+    mem.id
+}
 
-    if !json_handling::get_path("settings.json").exists() {
-        json_handling::write_settings(false, json_handling::WindowPosition { x: 100, y: 100 });
+/// Orders the entries for display according to the user's sort preference.
+fn sorted_entries(entries: &[TaskEntry], order: SortOrder) -> Vec<&TaskEntry> {
+    let mut sorted: Vec<&TaskEntry> = entries.iter().collect();
+    match order {
+        SortOrder::Manual => sorted.sort_by_key(|entry| entry.position),
+        SortOrder::Created => sorted.sort_by_key(|entry| entry.id),
+        SortOrder::Name => sorted.sort_by_key(|entry| entry.name.to_lowercase()),
+        SortOrder::OpenFirst => sorted.sort_by_key(|entry| (entry.completed, entry.id)),
+        // Newest first, tasks without a timestamp go last.
+        SortOrder::RecentlyUpdated => {
+            sorted.sort_by_key(|entry| std::cmp::Reverse((entry.updated_at, entry.id)))
+        }
+        SortOrder::RecentlyCompleted => {
+            sorted.sort_by_key(|entry| std::cmp::Reverse((entry.completed_at, entry.id)))
+        }
     }
+    sorted
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Tasks {
+    tasks: Vec<TaskEntry>,
+    #[serde(default)]
+    meta: ListMeta,
+}
+
+/// Details stored with a list besides its tasks.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct ListMeta {
+    /// The name shown for the list, the file name is a slug of it.
+    name: String,
+    description: String,
+    color: ListColor,
+    icon: ListIcon,
+    /// Overrides the sort order from the settings for this list.
+    sort_order: Option<SortOrder>,
+    /// Slot among the pinned lists, pinned lists come first in the picker.
+    pinned: Option<u32>,
+    /// Folder path such as `work/clients`, empty for the top level.
+    folder: String,
+    view: ListView,
+    /// The workflow of the list from first to last, empty uses
+    /// `DEFAULT_STATUSES`. Statuses are also the columns of the board.
+    #[serde(alias = "columns")]
+    statuses: Vec<Status>,
+    /// The id the next new task gets. It only ever goes up, ids of deleted
+    /// tasks are not handed out again.
+    next_id: i32,
+}
 
-    let settings = json_handling::read_settings().unwrap_or(json_handling::Settings::default());
-    mem.run = settings.run;
+const DEFAULT_STATUSES: [(&str, bool); 4] = [
+    ("Todo", false),
+    ("Doing", false),
+    ("Blocked", false),
+    ("Done", true),
+];
 
-    if (!mem.run && id == 0) {
-        mem.id = 0;
-        id = 0;
-    } else if mem.run == true {
-        id += 1;
-        mem.id = id;
+impl ListMeta {
+    /// The statuses of the list with exactly one of them marked done, the
+    /// last one unless another is.
+    fn statuses(&self) -> Vec<Status> {
+        if self.statuses.len() < 2 {
+            return DEFAULT_STATUSES
+                .iter()
+                .map(|(name, done)| Status {
+                    name: name.to_string(),
+                    done: *done,
+                })
+                .collect();
+        }
+        let mut statuses = self.statuses.clone();
+        let done = statuses
+            .iter()
+            .position(|status| status.done)
+            .unwrap_or(statuses.len() - 1);
+        for (index, status) in statuses.iter_mut().enumerate() {
+            status.done = index == done;
+        }
+        statuses
     }
+}
+
+/// A step in the workflow of a list, tasks in the done status count as
+/// completed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "StoredStatus")]
+pub(crate) struct Status {
+    name: String,
+    done: bool,
+}
+
+/// Board columns were stored as bare names before statuses could be marked
+/// done.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredStatus {
+    Name(String),
+    Status {
+        name: String,
+        #[serde(default)]
+        done: bool,
+    },
+}
 
-    if tasks.tasks.is_empty() {
-        id = 0;
-        mem.id = id;
+impl From<StoredStatus> for Status {
+    fn from(stored: StoredStatus) -> Self {
+        match stored {
+            StoredStatus::Name(name) => Status { name, done: false },
+            StoredStatus::Status { name, done } => Status { name, done },
+        }
     }
-    if !settings.run {
-        json_handling::write_settings(true, json_handling::WindowPosition { x: 100, y: 100 });
+}
+
+/// Narrows the open list down to one status.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum StatusFilter {
+    All,
+    /// Open tasks that are not waiting on another task.
+    Actionable,
+    Only(String),
+}
+
+impl std::fmt::Display for StatusFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StatusFilter::All => write!(f, "All statuses"),
+            StatusFilter::Actionable => write!(f, "Actionable"),
+            StatusFilter::Only(name) => write!(f, "{}", name),
+        }
     }
+}
 
-    return id;
+/// How the tasks of a list are laid out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum ListView {
+    #[default]
+    List,
+    Board,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct Tasks {
-    tasks: Vec<TaskEntry>,
+/// The board column a task sits in, tasks with an unknown status go to the
+/// done column or the first one.
+fn board_column(task: &TaskEntry, statuses: &[Status]) -> usize {
+    statuses
+        .iter()
+        .position(|status| status.name == task.status)
+        .or_else(|| {
+            statuses
+                .iter()
+                .position(|status| task.completed && status.done)
+        })
+        .unwrap_or(0)
+}
+
+/// A `key:value` word in the search box that narrows the tasks down instead
+/// of being matched against their text.
+enum SearchFilter {
+    /// Statuses starting with the value.
+    Status(String),
+    CreatedSince(NaiveDate),
+    UpdatedSince(NaiveDate),
+    CompletedSince(NaiveDate),
+}
+
+impl SearchFilter {
+    /// Reads `status:doing`, `completed:week`, `updated:today` or
+    /// `created:30d`. Dates are `today`, `week`, `month` or a number of days.
+    fn parse(word: &str, today: NaiveDate, week_start: WeekStart) -> Option<SearchFilter> {
+        let (key, value) = word.split_once(':')?;
+        if key == "status" {
+            return Some(SearchFilter::Status(value.to_string()));
+        }
+        let since = match value {
+            "today" => today,
+            "week" => calendar::start_of_week(today, week_start),
+            "month" => today.with_day(1)?,
+            // Counts that reach back past the earliest date are not filters.
+            days => {
+                let days: u32 = days.trim_end_matches('d').parse().ok()?;
+                today.checked_sub_days(chrono::Days::new(u64::from(days)))?
+            }
+        };
+        match key {
+            "created" => Some(SearchFilter::CreatedSince(since)),
+            "updated" => Some(SearchFilter::UpdatedSince(since)),
+            "completed" => Some(SearchFilter::CompletedSince(since)),
+            _ => None,
+        }
+    }
+
+    fn matches(&self, entry: &TaskEntry) -> bool {
+        let on_or_after = |time: Option<DateTime<Local>>, since: &NaiveDate| {
+            time.is_some_and(|time| time.date_naive() >= *since)
+        };
+        match self {
+            SearchFilter::Status(prefix) => entry.status.to_lowercase().starts_with(prefix),
+            SearchFilter::CreatedSince(since) => on_or_after(entry.created_at, since),
+            SearchFilter::UpdatedSince(since) => on_or_after(entry.updated_at, since),
+            SearchFilter::CompletedSince(since) => on_or_after(entry.completed_at, since),
+        }
+    }
+}
+
+/// Splits the comma separated status names from the details card.
+fn parse_statuses(input: &str) -> Result<Vec<String>, String> {
+    let mut statuses: Vec<String> = Vec::new();
+    for status in input
+        .split(',')
+        .map(str::trim)
+        .filter(|status| !status.is_empty())
+    {
+        if statuses
+            .iter()
+            .any(|other| other.eq_ignore_ascii_case(status))
+        {
+            return Err(format!("The status \"{}\" is listed twice.", status));
+        }
+        statuses.push(status.to_string());
+    }
+    match statuses.len() {
+        0 => Ok(Vec::new()),
+        1 => Err("A list needs at least two statuses.".to_string()),
+        _ => Ok(statuses),
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum ListColor {
+    #[default]
+    Theme,
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Teal,
+    Blue,
+    Purple,
+    Pink,
+}
+
+impl ListColor {
+    const ALL: [ListColor; 9] = [
+        ListColor::Theme,
+        ListColor::Red,
+        ListColor::Orange,
+        ListColor::Yellow,
+        ListColor::Green,
+        ListColor::Teal,
+        ListColor::Blue,
+        ListColor::Purple,
+        ListColor::Pink,
+    ];
+
+    /// The accent, `None` keeps the theme's own color.
+    fn color(&self) -> Option<Color> {
+        let (r, g, b) = match self {
+            ListColor::Theme => return None,
+            ListColor::Red => (0xE0, 0x4F, 0x4F),
+            ListColor::Orange => (0xE8, 0x8A, 0x2E),
+            ListColor::Yellow => (0xE3, 0xC1, 0x2B),
+            ListColor::Green => (0x4C, 0xAF, 0x50),
+            ListColor::Teal => (0x26, 0xA6, 0x9A),
+            ListColor::Blue => (0x42, 0x8B, 0xDB),
+            ListColor::Purple => (0x9C, 0x5B, 0xD6),
+            ListColor::Pink => (0xD9, 0x5B, 0xA0),
+        };
+        Some(Color::from_rgb8(r, g, b))
+    }
+}
+
+impl std::fmt::Display for ListColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ListColor::Theme => write!(f, "Theme accent"),
+            ListColor::Red => write!(f, "Red"),
+            ListColor::Orange => write!(f, "Orange"),
+            ListColor::Yellow => write!(f, "Yellow"),
+            ListColor::Green => write!(f, "Green"),
+            ListColor::Teal => write!(f, "Teal"),
+            ListColor::Blue => write!(f, "Blue"),
+            ListColor::Purple => write!(f, "Purple"),
+            ListColor::Pink => write!(f, "Pink"),
+        }
+    }
+}
+
+/// The icons a list can pick from, drawn with the `iced_aw` icon font.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum ListIcon {
+    #[default]
+    ListTask,
+    Inbox,
+    Star,
+    Heart,
+    Flag,
+    Briefcase,
+    House,
+    Cart,
+    Book,
+    Calendar,
+    Lightning,
+    Trophy,
+}
+
+impl ListIcon {
+    const ALL: [ListIcon; 12] = [
+        ListIcon::ListTask,
+        ListIcon::Inbox,
+        ListIcon::Star,
+        ListIcon::Heart,
+        ListIcon::Flag,
+        ListIcon::Briefcase,
+        ListIcon::House,
+        ListIcon::Cart,
+        ListIcon::Book,
+        ListIcon::Calendar,
+        ListIcon::Lightning,
+        ListIcon::Trophy,
+    ];
+
+    fn icon(&self) -> Icon {
+        match self {
+            ListIcon::ListTask => Icon::ListTask,
+            ListIcon::Inbox => Icon::Inbox,
+            ListIcon::Star => Icon::Star,
+            ListIcon::Heart => Icon::Heart,
+            ListIcon::Flag => Icon::Flag,
+            ListIcon::Briefcase => Icon::Briefcase,
+            ListIcon::House => Icon::House,
+            ListIcon::Cart => Icon::Cart,
+            ListIcon::Book => Icon::Book,
+            ListIcon::Calendar => Icon::Calendar,
+            ListIcon::Lightning => Icon::Lightning,
+            ListIcon::Trophy => Icon::Trophy,
+        }
+    }
+}
+
+impl std::fmt::Display for ListIcon {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ListIcon::ListTask => write!(f, "Checklist"),
+            ListIcon::Inbox => write!(f, "Inbox"),
+            ListIcon::Star => write!(f, "Star"),
+            ListIcon::Heart => write!(f, "Heart"),
+            ListIcon::Flag => write!(f, "Flag"),
+            ListIcon::Briefcase => write!(f, "Briefcase"),
+            ListIcon::House => write!(f, "House"),
+            ListIcon::Cart => write!(f, "Cart"),
+            ListIcon::Book => write!(f, "Book"),
+            ListIcon::Calendar => write!(f, "Calendar"),
+            ListIcon::Lightning => write!(f, "Lightning"),
+            ListIcon::Trophy => write!(f, "Trophy"),
+        }
+    }
+}
+
+/// Sort choice in the list details, either the global setting or a fixed
+/// order for this list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ListSortChoice {
+    Settings,
+    Fixed(SortOrder),
+}
+
+impl ListSortChoice {
+    fn all() -> Vec<ListSortChoice> {
+        std::iter::once(ListSortChoice::Settings)
+            .chain(SortOrder::ALL.into_iter().map(ListSortChoice::Fixed))
+            .collect()
+    }
+}
+
+impl std::fmt::Display for ListSortChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ListSortChoice::Settings => write!(f, "From settings"),
+            ListSortChoice::Fixed(order) => write!(f, "{}", order),
+        }
+    }
+}
+
+/// Views in the sidebar that collect tasks from every list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SmartView {
+    Today,
+    Upcoming,
+    Completed,
+    RecentlyDone,
+    All,
+}
+
+impl SmartView {
+    const ALL: [SmartView; 5] = [
+        SmartView::Today,
+        SmartView::Upcoming,
+        SmartView::Completed,
+        SmartView::RecentlyDone,
+        SmartView::All,
+    ];
+
+    /// How far back Recently done looks.
+    const RECENT_DAYS: i64 = 7;
+
+    /// How far ahead Upcoming looks.
+    const UPCOMING_DAYS: i64 = 7;
+
+    fn contains(&self, task: &TaskEntry, today: NaiveDate) -> bool {
+        match self {
+            SmartView::Today => {
+                !task.completed && task.due_date.is_some_and(|due_date| due_date <= today)
+            }
+            SmartView::Upcoming => {
+                !task.completed
+                    && task.due_date.is_some_and(|due_date| {
+                        due_date > today
+                            && due_date <= today + chrono::Duration::days(Self::UPCOMING_DAYS)
+                    })
+            }
+            SmartView::Completed => task.completed,
+            SmartView::RecentlyDone => task.completed_at.is_some_and(|completed_at| {
+                completed_at.date_naive() > today - chrono::Duration::days(Self::RECENT_DAYS)
+            }),
+            SmartView::All => true,
+        }
+    }
+}
+
+impl std::fmt::Display for SmartView {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SmartView::Today => write!(f, "Today"),
+            SmartView::Upcoming => write!(f, "Upcoming"),
+            SmartView::Completed => write!(f, "Completed"),
+            SmartView::RecentlyDone => write!(f, "Done last 7 days"),
+            SmartView::All => write!(f, "All"),
+        }
+    }
+}
+
+/// Sections of the agenda, open tasks from every list by due date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum AgendaSection {
+    Overdue,
+    Today,
+    Tomorrow,
+    ThisWeek,
+    Later,
+    NoDate,
+}
+
+impl AgendaSection {
+    const ALL: [AgendaSection; 6] = [
+        AgendaSection::Overdue,
+        AgendaSection::Today,
+        AgendaSection::Tomorrow,
+        AgendaSection::ThisWeek,
+        AgendaSection::Later,
+        AgendaSection::NoDate,
+    ];
+
+    /// The section of an open task, completed tasks are left out.
+    fn of(task: &TaskEntry, today: NaiveDate, week_start: WeekStart) -> Option<AgendaSection> {
+        if task.completed {
+            return None;
+        }
+        let Some(due_date) = task.due_date else {
+            return Some(AgendaSection::NoDate);
+        };
+        let week_end = calendar::start_of_week(today, week_start) + chrono::Duration::days(6);
+        Some(match (due_date - today).num_days() {
+            days if days < 0 => AgendaSection::Overdue,
+            0 => AgendaSection::Today,
+            1 => AgendaSection::Tomorrow,
+            _ if due_date <= week_end => AgendaSection::ThisWeek,
+            _ => AgendaSection::Later,
+        })
+    }
+}
+
+impl std::fmt::Display for AgendaSection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AgendaSection::Overdue => write!(f, "Overdue"),
+            AgendaSection::Today => write!(f, "Today"),
+            AgendaSection::Tomorrow => write!(f, "Tomorrow"),
+            AgendaSection::ThisWeek => write!(f, "This week"),
+            AgendaSection::Later => write!(f, "Later"),
+            AgendaSection::NoDate => write!(f, "No date"),
+        }
+    }
+}
+
+/// A task together with the list it belongs to, for the smart views.
+#[derive(Debug, Clone)]
+struct ListedTask {
+    list: String,
+    list_name: String,
+    entry: TaskEntry,
+}
+
+fn today() -> NaiveDate {
+    chrono::Local::now().date_naive()
+}
+
+/// Below this window width the sidebar folds away on its own.
+const SIDEBAR_MIN_WINDOW_WIDTH: u32 = 640;
+
+/// The entry for `file` in `lists`, used to preselect pickers.
+fn list_info(lists: &[ListInfo], file: Option<&str>) -> Option<ListInfo> {
+    lists
+        .iter()
+        .find(|list| Some(list.file.as_str()) == file)
+        .cloned()
 }
 
 impl Default for Tasks {
     fn default() -> Self {
         Self {
             tasks: vec![TaskEntry::default()],
+            meta: ListMeta::default(),
         }
     }
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-struct TaskEntry {
+impl Tasks {
+    /// The id for a new task, past every id the list has handed out.
+    fn next_id(&self) -> i32 {
+        self.tasks
+            .iter()
+            .map(|task| task.id + 1)
+            .max()
+            .unwrap_or(0)
+            .max(self.meta.next_id)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub(crate) struct TaskEntry {
     id: i32,
     name: String,
     description: String,
     tags: Vec<String>,
     completed: bool,
+    /// Place of the task in the manual sort order.
+    #[serde(default)]
+    position: u32,
+    #[serde(default)]
+    priority: Priority,
+    #[serde(default)]
+    due_date: Option<NaiveDate>,
+    #[serde(default, deserialize_with = "estimate::lenient")]
+    estimate: Option<Estimate>,
+    /// When to send a notification, cleared once it has been sent.
+    #[serde(default)]
+    remind_at: Option<DateTime<Local>>,
+    /// Name of one of the list's statuses, `completed` follows it.
+    #[serde(default)]
+    status: String,
+    /// Tasks that have to be completed before this one can start.
+    #[serde(default)]
+    blocked_by: Vec<TaskRef>,
+    /// Set by `json_handling::write_task`, lists written before timestamps
+    /// existed have none.
+    #[serde(default)]
+    created_at: Option<DateTime<Local>>,
+    #[serde(default)]
+    updated_at: Option<DateTime<Local>>,
+    #[serde(default)]
+    completed_at: Option<DateTime<Local>>,
+    /// Time tracked on the task, at most one session across all lists is
+    /// running at a time.
+    #[serde(default)]
+    time_sessions: Vec<TimeSession>,
+    /// When each pomodoro finished on this task.
+    #[serde(default)]
+    pomodoros: Vec<DateTime<Local>>,
+}
+
+/// The parsed inputs of the Create and Edit cards.
+struct TaskFields {
+    due_date: Option<NaiveDate>,
+    estimate: Option<Estimate>,
+    remind_at: Option<DateTime<Local>>,
+}
+
+/// Points at a task in any list. Ids are never renumbered, so a reference
+/// stays valid until its task is deleted or moved.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub(crate) struct TaskRef {
+    list: String,
+    id: i32,
+}
+
+/// A task offered in the blocked-by picker of the Edit card.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BlockerChoice {
+    task: TaskRef,
+    label: String,
+}
+
+impl std::fmt::Display for BlockerChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum Priority {
+    #[default]
+    None,
+    Low,
+    Medium,
+    High,
+}
+
+impl Priority {
+    const ALL: [Priority; 4] = [
+        Priority::None,
+        Priority::Low,
+        Priority::Medium,
+        Priority::High,
+    ];
+}
+
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Priority::None => write!(f, "None"),
+            Priority::Low => write!(f, "Low"),
+            Priority::Medium => write!(f, "Medium"),
+            Priority::High => write!(f, "High"),
+        }
+    }
+}
+
+/// Splits the stored tags into single tags, older entries keep all of them
+/// in one comma separated string.
+fn split_tags(tags: &[String]) -> Vec<String> {
+    tags.iter()
+        .flat_map(|tag| tag.split(','))
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+impl TaskEntry {
+    fn is_overdue(&self, today: NaiveDate) -> bool {
+        !self.completed && self.due_date.is_some_and(|due_date| due_date < today)
+    }
+
+    fn set_status(&mut self, status: &Status) {
+        self.status = status.name.clone();
+        self.completed = status.done;
+    }
+
+    /// Completes the task or reopens it in the first open status.
+    fn set_completed(&mut self, completed: bool, statuses: &[Status]) {
+        if self.completed == completed {
+            return;
+        }
+        if let Some(status) = statuses.iter().find(|status| status.done == completed) {
+            self.set_status(status);
+        }
+    }
 }
 
 impl Default for TaskEntry {
@@ -90,6 +760,18 @@ impl Default for TaskEntry {
             description: String::default(),
             tags: vec![String::default()],
             completed: false,
+            position: 0,
+            priority: Priority::None,
+            due_date: None,
+            estimate: None,
+            remind_at: None,
+            status: String::default(),
+            blocked_by: Vec::new(),
+            created_at: None,
+            updated_at: None,
+            completed_at: None,
+            time_sessions: Vec::new(),
+            pomodoros: Vec::new(),
         }
     }
 }
@@ -97,6 +779,8 @@ impl Default for TaskEntry {
 pub struct TaskList {
     state: State,
     button_pressed: Option<ButtonPressed>,
+    settings: Settings,
+    themes: Vec<ThemeDefinition>,
     mem: Mem,
 }
 
@@ -109,11 +793,85 @@ struct Mem {
     file_name: String,
     list_name: String,
     search_term: String,
-    search_results: Vec<TaskEntry>,
     selected_file: Option<String>,
+    lists: Vec<ListInfo>,
+    all_tasks: Vec<ListedTask>,
+    smart_view: Option<SmartView>,
+    calendar: Option<CalendarSpan>,
+    agenda: bool,
+    calendar_anchor: NaiveDate,
+    calendar_drag: Option<(String, i32)>,
+    pressed_day: Option<NaiveDate>,
+    list_meta: ListMeta,
+    details_draft: ListMeta,
+    statuses_input: String,
+    done_status_choice: Option<String>,
+    status_filter: StatusFilter,
+    new_list_error: Option<String>,
+    delete_list_error: Option<String>,
     task_tags: Vec<String>,
+    task_due: String,
+    task_estimate: String,
+    task_reminder: String,
+    task_blockers: Vec<TaskRef>,
+    task_error: Option<String>,
+    deleting_task_id: Option<i32>,
+    settings_draft: Settings,
+    font_size_input: String,
+    backup_retention_input: String,
+    focus_work_input: String,
+    focus_break_input: String,
+    focus: Option<Focus>,
+    /// Reminders that went off and have not been answered yet.
+    reminders: Vec<TaskRef>,
+    notifications: reminders::Shown,
+    launched_at: DateTime<Local>,
+    window_geometry: WindowGeometry,
+    /// Position and size reported since the geometry was last saved.
+    window_moved: Option<(i32, i32)>,
+    window_resized: Option<(u32, u32)>,
+    focused_task: Option<i32>,
+    /// Tasks whose description and less used actions are unfolded in the
+    /// list.
+    expanded_tasks: BTreeSet<i32>,
+    keymap_inputs: BTreeMap<Action, String>,
+    dragging_task: Option<i32>,
+    dragging_list: Option<String>,
+    pressed_folder: Option<String>,
+    modifiers: Modifiers,
+    /// Whether the search bar has keyboard focus, the only text input next
+    /// to the task list. iced does not report focus changes, it is looked up
+    /// after clicks and Escape.
+    input_focused: bool,
+    selected_tasks: BTreeSet<i32>,
+    selection_anchor: Option<i32>,
+    bulk_tag: String,
+    bulk_due_date: String,
+    bulk_target: Option<String>,
+    bulk_error: Option<String>,
+    transfer_task_id: Option<i32>,
+    transfer_keep_source: bool,
+    transfer_target: Option<String>,
+    transfer_error: Option<String>,
+    history_task_id: Option<i32>,
+    history: Vec<history::Change>,
+    history_error: Option<String>,
+    report_from: String,
+    report_to: String,
+    report_group: ReportGroup,
+    report_error: Option<String>,
+    rename_input: String,
+    duplicate_input: String,
+    duplicate_reset_completed: bool,
+    archived_choice: Option<String>,
+    /// Archived lists offered in the Manage card, read when it opens.
+    archived_lists: Vec<ListInfo>,
+    list_error: Option<String>,
+    palette_query: String,
+    palette_items: Vec<PaletteItem>,
+    palette_selected: usize,
+    status: Option<String>,
     id: i32,
-    run: bool,
 }
 impl Default for Mem {
     fn default() -> Self {
@@ -122,14 +880,86 @@ impl Default for Mem {
             task_desc: String::new(),
             task_entries: vec![TaskEntry::default()],
             search_term: String::new(),
-            search_results: vec![TaskEntry::default()],
             editing_task_id: None,
             file_name: String::new(),
             list_name: "tasklist".to_string(),
             selected_file: Some("tasklist".to_string()),
+            lists: Vec::new(),
+            all_tasks: Vec::new(),
+            smart_view: None,
+            calendar: None,
+            agenda: false,
+            calendar_anchor: today(),
+            calendar_drag: None,
+            pressed_day: None,
+            list_meta: ListMeta::default(),
+            details_draft: ListMeta::default(),
+            statuses_input: String::new(),
+            done_status_choice: None,
+            status_filter: StatusFilter::All,
+            new_list_error: None,
+            delete_list_error: None,
             task_tags: vec![String::new()],
+            task_due: String::new(),
+            task_estimate: String::new(),
+            task_reminder: String::new(),
+            task_blockers: Vec::new(),
+            task_error: None,
+            deleting_task_id: None,
+            settings_draft: Settings::default(),
+            font_size_input: String::new(),
+            backup_retention_input: String::new(),
+            focus_work_input: String::new(),
+            focus_break_input: String::new(),
+            focus: None,
+            reminders: Vec::new(),
+            notifications: reminders::Shown::default(),
+            launched_at: Local::now(),
+            window_geometry: WindowGeometry {
+                x: 100,
+                y: 100,
+                width: 800,
+                height: 335,
+                maximized: false,
+            },
+            window_moved: None,
+            window_resized: None,
+            focused_task: None,
+            expanded_tasks: BTreeSet::new(),
+            keymap_inputs: BTreeMap::new(),
+            dragging_task: None,
+            dragging_list: None,
+            pressed_folder: None,
+            modifiers: Modifiers::default(),
+            input_focused: false,
+            selected_tasks: BTreeSet::new(),
+            selection_anchor: None,
+            bulk_tag: String::new(),
+            bulk_due_date: String::new(),
+            bulk_target: None,
+            bulk_error: None,
+            transfer_task_id: None,
+            transfer_keep_source: false,
+            transfer_target: None,
+            transfer_error: None,
+            history_task_id: None,
+            history: Vec::new(),
+            history_error: None,
+            report_from: String::new(),
+            report_to: String::new(),
+            report_group: ReportGroup::Task,
+            report_error: None,
+            rename_input: String::new(),
+            duplicate_input: String::new(),
+            duplicate_reset_completed: false,
+            archived_choice: None,
+            archived_lists: Vec::new(),
+            list_error: None,
+            palette_query: String::new(),
+            palette_items: Vec::new(),
+            palette_selected: 0,
+            status: None,
             id: 0,
-            run: false,
         }
     }
 }
@@ -139,12 +969,20 @@ pub enum Message {
     FontLoaded(Result<(), font::Error>),
     ButtonEditPressed(i32),
     ButtonCreatePressed,
-    ButtonDeletePressed(usize),
+    ButtonDeletePressed(i32),
+    DeleteTask,
     TaskNameChanged(String),
     TaskNameEdited(String),
-    TaskDescChanged(String),
-    TaskDescEdited(String),
+    DescriptionLineChanged(usize, String),
+    DescriptionLineSplit(usize, usize, usize),
+    DescriptionLinesJoined(usize),
+    DescriptionLineRemoved(usize),
     TagsChanged(String),
+    TaskDueChanged(String),
+    TaskEstimateChanged(String),
+    TaskReminderChanged(String),
+    BlockerSelected(BlockerChoice),
+    BlockerRemoved(TaskRef),
     NewFileNameChanged(String),
     TaskSubmitted,
     TaskEdited(i32),
@@ -156,13 +994,136 @@ pub enum Message {
     DeleteList,
     SearchChanged(String),
     SearchButtonPressed,
+    SettingsButtonPressed,
+    SettingsThemeChanged(String),
+    SettingsDefaultListChanged(String),
+    SettingsStartupChanged(StartupBehavior),
+    SettingsSortOrderChanged(SortOrder),
+    SettingsConfirmTaskChanged(bool),
+    SettingsConfirmListChanged(bool),
+    SettingsDateFormatChanged(DateFormat),
+    SettingsWeekStartChanged(WeekStart),
+    SettingsFontSizeChanged(String),
+    SettingsBackupRetentionChanged(String),
+    SettingsFocusWorkChanged(String),
+    SettingsFocusBreakChanged(String),
+    SettingsKeymapChanged(Action, String),
+    SettingsSaved,
+    WindowMoved(i32, i32),
+    WindowResized(u32, u32),
+    WindowCloseRequested,
+    SaveWindowGeometry,
+    KeyPressed(KeyCode, Modifiers),
+    ModifiersChanged(Modifiers),
+    CheckInputFocus,
+    InputFocused(widget::Id),
+    SelectAll,
+    ClearSelection,
+    BulkPressed,
+    BulkSetCompleted(bool),
+    BulkDelete,
+    BulkTagChanged(String),
+    BulkAddTag,
+    BulkRemoveTag,
+    BulkPrioritySelected(Priority),
+    BulkDueDateChanged(String),
+    BulkSetDueDate,
+    BulkClearDueDate,
+    BulkTargetSelected(String),
+    BulkMove,
+    BulkCopy,
+    ToggleSidebar,
+    ListPressed(String),
+    ListReleased(String),
+    FolderPressed(String),
+    FolderReleased(String),
+    DetailsFolderChanged(String),
+    DetailsStatusesChanged(String),
+    DetailsDoneStatusSelected(String),
+    StatusSelected(i32, String),
+    StatusFilterSelected(StatusFilter),
+    ToggleBoard,
+    BoardDropped(usize),
+    SmartViewSelected(SmartView),
+    CalendarOpened,
+    AgendaOpened,
+    ListedTaskEditPressed(String, i32),
+    CalendarSpanSelected(CalendarSpan),
+    CalendarStepped(bool),
+    CalendarToday,
+    CalendarTaskPressed(String, i32),
+    CalendarDayPressed(NaiveDate),
+    CalendarDayReleased(NaiveDate),
+    ListedTaskToggled(String, i32, bool),
+    ListedTaskOpened(String, i32),
+    ListDetailsPressed,
+    DetailsDescriptionChanged(String),
+    DetailsColorSelected(ListColor),
+    DetailsIconSelected(ListIcon),
+    DetailsSortSelected(ListSortChoice),
+    DetailsPinnedToggled(bool),
+    SaveListDetails,
+    ManageListPressed,
+    RenameInputChanged(String),
+    RenameList,
+    DuplicateInputChanged(String),
+    DuplicateResetToggled(bool),
+    DuplicateList,
+    ArchiveList,
+    ArchivedListSelected(String),
+    RestoreList,
+    ButtonMovePressed(i32),
+    ButtonCopyPressed(i32),
+    TransferTargetSelected(String),
+    TransferTask,
+    ButtonHistoryPressed(i32),
+    RevertChange(usize),
+    TimerToggled(String, i32),
+    Tick,
+    FocusStarted(String, i32),
+    FocusPauseToggled,
+    FocusSkipped,
+    FocusCompleteToggled(bool),
+    FocusStopped,
+    ToggleTaskDetails(i32),
+    LinkClicked(String),
+    ReminderCheck,
+    ReminderAnswered(TaskRef, Option<ReminderAction>),
+    ReminderDismissed(TaskRef),
+    TimeReportPressed,
+    ReportFromChanged(String),
+    ReportToChanged(String),
+    ReportGroupSelected(ReportGroup),
+    ExportTimeReport,
+    DragStarted(i32),
+    DragDropped(i32),
+    DragEnded,
+    PaletteQueryChanged(String),
+    PaletteSubmitted,
+    PaletteCommandChosen(PaletteCommand),
     CloseOverlay,
 }
 
-fn modify_task(mem: &mut Mem, name: String, description: String) {
-    mem.task_name = name;
-    mem.task_desc = description;
+fn search_input_id() -> text_input::Id {
+    text_input::Id::new("search")
+}
+
+fn task_name_input_id() -> text_input::Id {
+    text_input::Id::new("task-name")
+}
+
+fn description_line_id(line: usize) -> text_input::Id {
+    text_input::Id::new(format!("description-{}", line))
+}
+
+fn palette_input_id() -> text_input::Id {
+    text_input::Id::new("command-palette")
 }
+
+fn task_scrollable_id() -> scrollable::Id {
+    scrollable::Id::new("tasks")
+}
+
 impl Application for TaskList {
     type Executor = executor::Default;
     type Message = Message;
@@ -170,18 +1131,60 @@ impl Application for TaskList {
     type Flags = ();
 
     fn new(_flags: Self::Flags) -> (Self, Command<Self::Message>) {
-        (
-            Self {
-                state: State::None,
-                button_pressed: None,
-                mem: Mem::default(),
+        let settings = json_handling::read_settings().unwrap_or_default();
+        let list_name = settings.startup_list();
+        let window_geometry = WindowGeometry {
+            x: settings.x,
+            y: settings.y,
+            width: settings.width,
+            height: settings.height,
+            maximized: settings.maximized,
+        };
+
+        let mut commands =
+            vec![font::load(iced_aw::graphics::icons::ICON_FONT_BYTES).map(Message::FontLoaded)];
+        if settings.maximized {
+            commands.push(window::maximize(true));
+        }
+
+        let agenda = settings.startup == StartupBehavior::Agenda;
+        let mut task_list = Self {
+            state: State::None,
+            button_pressed: None,
+            settings,
+            themes: Self::load_themes(),
+            mem: Mem {
+                list_name: list_name.clone(),
+                selected_file: Some(list_name),
+                window_geometry,
+                agenda,
+                ..Mem::default()
             },
-            font::load(iced_aw::graphics::icons::ICON_FONT_BYTES).map(Message::FontLoaded),
-        )
+        };
+        task_list.refresh_lists();
+        // Reminders that came due while the app was closed go off right away.
+        commands.push(task_list.check_reminders());
+
+        (task_list, Command::batch(commands))
     }
 
     fn title(&self) -> String {
-        String::from("Tasks")
+        if let Some(focus) = &self.mem.focus {
+            return format!(
+                "{} {} — {}",
+                focus.phase,
+                focus::format_countdown(focus.remaining(Local::now())),
+                focus.name
+            );
+        }
+        match self.running_timer() {
+            Some((task, session)) => format!(
+                "Tasks — {} {}",
+                task.entry.name,
+                time_tracking::format_clock(Local::now() - session.start)
+            ),
+            None => String::from("Tasks"),
+        }
     }
 
     fn update(&mut self, message: Self::Message) -> Command<Self::Message> {
@@ -190,6 +1193,7 @@ impl Application for TaskList {
                 let tasks = json_handling::read_tasks(self.mem.list_name.clone())
                     .unwrap_or_else(|_| Tasks::default());
                 self.mem.task_entries = tasks.tasks;
+                self.mem.list_meta = tasks.meta;
                 Command::none()
             }
             Message::ButtonCreatePressed => {
@@ -198,11 +1202,22 @@ impl Application for TaskList {
                         self.button_pressed = {
                             self.state = State::Create;
                             Some(ButtonPressed::Create)
-                        }
-                    }
-                    State::Edit | State::Create | State::NewFile | State::DeleteList => {
-                        self.button_pressed = None
+                        };
+                        return text_input::focus(task_name_input_id());
                     }
+                    State::Edit
+                    | State::Create
+                    | State::NewFile
+                    | State::DeleteList
+                    | State::DeleteTask
+                    | State::Settings
+                    | State::CommandPalette
+                    | State::Bulk
+                    | State::Transfer
+                    | State::ManageList
+                    | State::ListDetails
+                    | State::History
+                    | State::TimeReport => self.button_pressed = None,
                 }
                 Command::none()
             }
@@ -220,27 +1235,120 @@ impl Application for TaskList {
                                 self.mem.task_name = task.name.clone();
                                 self.mem.task_desc = task.description.clone();
                                 self.mem.task_tags = task.tags.clone();
+                                self.mem.task_blockers = task.blocked_by.clone();
+                                self.mem.task_due = task
+                                    .due_date
+                                    .map(|due_date| {
+                                        due_date
+                                            .format(self.settings.date_format.pattern())
+                                            .to_string()
+                                    })
+                                    .unwrap_or_default();
+                                self.mem.task_estimate = task
+                                    .estimate
+                                    .map(|estimate| estimate.to_string())
+                                    .unwrap_or_default();
+                                self.mem.task_reminder = task
+                                    .remind_at
+                                    .map(|remind_at| {
+                                        remind_at.format(&self.reminder_pattern()).to_string()
+                                    })
+                                    .unwrap_or_default();
                             }
                             self.state = State::Edit;
                             Some(ButtonPressed::Edit)
-                        }
+                        };
+                        return text_input::focus(task_name_input_id());
                     }
-                    State::Create | State::Edit | State::NewFile | State::DeleteList => {
-                        self.button_pressed = None
+                    State::Create
+                    | State::Edit
+                    | State::NewFile
+                    | State::DeleteList
+                    | State::DeleteTask
+                    | State::Settings
+                    | State::CommandPalette
+                    | State::Bulk
+                    | State::Transfer
+                    | State::ManageList
+                    | State::ListDetails
+                    | State::History
+                    | State::TimeReport => self.button_pressed = None,
+                }
+                Command::none()
+            }
+            Message::ButtonDeletePressed(id) => {
+                if !self.settings.confirm_delete_task {
+                    let changed = self
+                        .saved(json_handling::delete_tasks(
+                            &[id],
+                            self.mem.list_name.clone(),
+                        ))
+                        .unwrap_or_default();
+                    self.mem.selected_tasks.clear();
+                    self.refresh_changed(&changed);
+                    let tasks =
+                        json_handling::read_tasks(self.mem.list_name.clone()).unwrap_or_default();
+                    self.mem.task_entries = tasks.tasks;
+                    return Command::none();
+                }
+
+                match self.state {
+                    State::None => {
+                        self.button_pressed = {
+                            self.mem.deleting_task_id = Some(id);
+                            self.state = State::DeleteTask;
+                            Some(ButtonPressed::DeleteTask)
+                        }
                     }
+                    State::Create
+                    | State::Edit
+                    | State::NewFile
+                    | State::DeleteList
+                    | State::DeleteTask
+                    | State::Settings
+                    | State::CommandPalette
+                    | State::Bulk
+                    | State::Transfer
+                    | State::ManageList
+                    | State::ListDetails
+                    | State::History
+                    | State::TimeReport => self.button_pressed = None,
                 }
                 Command::none()
             }
-            Message::ButtonDeletePressed(index) => {
-                json_handling::delete_tasks(index as i32, self.mem.list_name.clone());
-                let tasks = json_handling::read_tasks(self.mem.list_name.clone()).unwrap();
-                self.mem.task_entries = tasks.tasks;
+            Message::DeleteTask => {
+                if let Some(id) = self.mem.deleting_task_id.take() {
+                    let changed = self
+                        .saved(json_handling::delete_tasks(
+                            &[id],
+                            self.mem.list_name.clone(),
+                        ))
+                        .unwrap_or_default();
+                    self.mem.selected_tasks.clear();
+                    self.refresh_changed(&changed);
+                    let tasks =
+                        json_handling::read_tasks(self.mem.list_name.clone()).unwrap_or_default();
+                    self.mem.task_entries = tasks.tasks;
+                }
+
+                match (&self.state, &self.button_pressed) {
+                    (State::DeleteTask, Some(ButtonPressed::DeleteTask)) => {
+                        self.state = State::None;
+                        self.button_pressed = None;
+                    }
+                    _ => println!("Attempted to close overlay while in another mode."),
+                };
                 Command::none()
             }
             Message::CloseOverlay => {
                 self.mem.task_name = String::new();
                 self.mem.task_desc = String::new();
                 self.mem.task_tags.clear();
+                self.mem.task_due = String::new();
+                self.mem.task_estimate = String::new();
+                self.mem.task_reminder = String::new();
+                self.mem.task_blockers.clear();
+                self.mem.task_error = None;
                 match (&self.state, &self.button_pressed) {
                     (State::Create, Some(ButtonPressed::Create)) => {
                         self.state = State::None;
@@ -253,11 +1361,70 @@ impl Application for TaskList {
                         Command::none()
                     }
                     (State::NewFile, Some(ButtonPressed::NewFile)) => {
+                        self.mem.file_name = String::new();
+                        self.mem.new_list_error = None;
                         self.state = State::None;
                         self.button_pressed = None;
                         Command::none()
                     }
                     (State::DeleteList, Some(ButtonPressed::DeleteList)) => {
+                        self.mem.delete_list_error = None;
+                        self.state = State::None;
+                        self.button_pressed = None;
+                        Command::none()
+                    }
+                    (State::DeleteTask, Some(ButtonPressed::DeleteTask)) => {
+                        self.mem.deleting_task_id = None;
+                        self.state = State::None;
+                        self.button_pressed = None;
+                        Command::none()
+                    }
+                    (State::Settings, Some(ButtonPressed::Settings)) => {
+                        self.state = State::None;
+                        self.button_pressed = None;
+                        Command::none()
+                    }
+                    (State::CommandPalette, Some(ButtonPressed::CommandPalette)) => {
+                        self.mem.palette_query = String::new();
+                        self.state = State::None;
+                        self.button_pressed = None;
+                        Command::none()
+                    }
+                    (State::Bulk, Some(ButtonPressed::Bulk)) => {
+                        self.mem.bulk_error = None;
+                        self.state = State::None;
+                        self.button_pressed = None;
+                        Command::none()
+                    }
+                    (State::ManageList, Some(ButtonPressed::ManageList)) => {
+                        self.mem.list_error = None;
+                        self.state = State::None;
+                        self.button_pressed = None;
+                        Command::none()
+                    }
+                    (State::ListDetails, Some(ButtonPressed::ListDetails)) => {
+                        self.mem.list_error = None;
+                        self.state = State::None;
+                        self.button_pressed = None;
+                        Command::none()
+                    }
+                    (State::Transfer, Some(ButtonPressed::Transfer)) => {
+                        self.mem.transfer_task_id = None;
+                        self.mem.transfer_error = None;
+                        self.state = State::None;
+                        self.button_pressed = None;
+                        Command::none()
+                    }
+                    (State::History, Some(ButtonPressed::History)) => {
+                        self.mem.history_task_id = None;
+                        self.mem.history.clear();
+                        self.mem.history_error = None;
+                        self.state = State::None;
+                        self.button_pressed = None;
+                        Command::none()
+                    }
+                    (State::TimeReport, Some(ButtonPressed::TimeReport)) => {
+                        self.mem.report_error = None;
                         self.state = State::None;
                         self.button_pressed = None;
                         Command::none()
@@ -273,12 +1440,57 @@ impl Application for TaskList {
                 self.mem.task_name = input;
                 Command::none()
             }
-            Message::TaskDescChanged(input) => {
-                self.mem.task_desc = input;
+            // The description is edited one line per input. Text inputs drop
+            // control characters from pasted text, so line breaks only come
+            // from splitting a line.
+            Message::DescriptionLineChanged(line, input) => {
+                let mut lines: Vec<&str> = self.mem.task_desc.split('\n').collect();
+                if let Some(current) = lines.get_mut(line) {
+                    *current = &input;
+                }
+                self.mem.task_desc = lines.join("\n");
                 Command::none()
             }
-            Message::TaskDescEdited(input) => {
-                self.mem.task_desc = input;
+            Message::DescriptionLineSplit(line, start, end) => {
+                let mut lines: Vec<String> =
+                    self.mem.task_desc.split('\n').map(String::from).collect();
+                let Some(current) = lines.get_mut(line) else {
+                    return Command::none();
+                };
+                let rest = current.get(end..).unwrap_or_default().to_string();
+                current.truncate(start.min(current.len()));
+                lines.insert(line + 1, rest);
+                self.mem.task_desc = lines.join("\n");
+                let id = description_line_id(line + 1);
+                Command::batch([
+                    text_input::focus(id.clone()),
+                    text_input::move_cursor_to_front(id),
+                ])
+            }
+            // Joins a line onto the end of the one above it, the cursor goes
+            // where the two meet.
+            Message::DescriptionLinesJoined(line) => {
+                let mut lines: Vec<&str> = self.mem.task_desc.split('\n').collect();
+                if line == 0 || line >= lines.len() {
+                    return Command::none();
+                }
+                let previous = lines[line - 1];
+                let position = text_input::Value::new(previous).len();
+                let joined = format!("{}{}", previous, lines.remove(line));
+                lines[line - 1] = &joined;
+                self.mem.task_desc = lines.join("\n");
+                let id = description_line_id(line - 1);
+                Command::batch([
+                    text_input::focus(id.clone()),
+                    text_input::move_cursor_to(id, position),
+                ])
+            }
+            Message::DescriptionLineRemoved(line) => {
+                let mut lines: Vec<&str> = self.mem.task_desc.split('\n').collect();
+                if lines.len() > 1 && line < lines.len() {
+                    lines.remove(line);
+                }
+                self.mem.task_desc = lines.join("\n");
                 Command::none()
             }
             Message::TagsChanged(input) => {
@@ -286,20 +1498,54 @@ impl Application for TaskList {
                 self.mem.task_tags.push(input);
                 Command::none()
             }
-            Message::TaskSubmitted => {
-                if self.mem.id > self.mem.task_entries.len() as i32 {
-                    self.mem.task_entries.resize(
-                        self.mem.task_entries.len() + 1,
-                        TaskEntry {
-                            id: self.mem.id,
-                            name: String::new(),
-                            description: String::new(),
-                            tags: vec![String::default()],
-                            completed: false,
-                        },
-                    );
+            Message::TaskDueChanged(input) => {
+                self.mem.task_due = input;
+                self.mem.task_error = None;
+                Command::none()
+            }
+            Message::TaskEstimateChanged(input) => {
+                self.mem.task_estimate = input;
+                self.mem.task_error = None;
+                Command::none()
+            }
+            Message::TaskReminderChanged(input) => {
+                self.mem.task_reminder = input;
+                self.mem.task_error = None;
+                Command::none()
+            }
+            Message::BlockerSelected(choice) => {
+                if let Some(id) = self.mem.editing_task_id {
+                    let editing = TaskRef {
+                        list: self.mem.list_name.clone(),
+                        id,
+                    };
+                    if self.depends_on(&choice.task, &editing) {
+                        self.mem.task_error =
+                            Some(format!("\"{}\" already waits on this task.", choice.label));
+                    } else if !self.mem.task_blockers.contains(&choice.task) {
+                        self.mem.task_blockers.push(choice.task);
+                        self.mem.task_error = None;
+                    }
                 }
-
+                Command::none()
+            }
+            Message::BlockerRemoved(blocker) => {
+                self.mem.task_blockers.retain(|other| *other != blocker);
+                self.mem.task_error = None;
+                Command::none()
+            }
+            Message::TaskSubmitted => {
+                let TaskFields {
+                    due_date,
+                    estimate,
+                    remind_at,
+                } = match self.parse_task_fields() {
+                    Ok(parsed) => parsed,
+                    Err(err) => {
+                        self.mem.task_error = Some(err);
+                        return Command::none();
+                    }
+                };
                 get_id(&mut self.mem);
 
                 let new_entry = TaskEntry {
@@ -308,21 +1554,48 @@ impl Application for TaskList {
                     description: self.mem.task_desc.clone(),
                     tags: vec![self.mem.task_tags.join(", ")],
                     completed: false,
+                    position: self
+                        .mem
+                        .task_entries
+                        .iter()
+                        .map(|entry| entry.position + 1)
+                        .max()
+                        .unwrap_or(0),
+                    priority: Priority::None,
+                    due_date,
+                    estimate,
+                    remind_at,
+                    status: String::new(),
+                    blocked_by: Vec::new(),
+                    created_at: None,
+                    updated_at: None,
+                    completed_at: None,
+                    time_sessions: Vec::new(),
+                    pomodoros: Vec::new(),
                 };
                 self.mem.task_entries.push(new_entry.clone());
 
                 let entry = Tasks {
                     tasks: self.mem.task_entries.clone(),
+                    meta: ListMeta::default(),
                 };
 
-                json_handling::write_task(&entry, self.mem.list_name.clone());
+                self.saved(json_handling::write_task(
+                    &entry,
+                    self.mem.list_name.clone(),
+                ));
 
-                let tasks = json_handling::read_tasks(self.mem.list_name.clone()).unwrap();
+                let tasks =
+                    json_handling::read_tasks(self.mem.list_name.clone()).unwrap_or_default();
                 self.mem.task_entries = tasks.tasks;
+                self.refresh_list(&self.mem.list_name.clone());
 
                 self.mem.task_name = String::new();
                 self.mem.task_desc = String::new();
                 self.mem.task_tags.clear();
+                self.mem.task_due = String::new();
+                self.mem.task_estimate = String::new();
+                self.mem.task_reminder = String::new();
 
                 match (&self.state, &self.button_pressed) {
                     (State::Create, Some(ButtonPressed::Create)) => {
@@ -335,6 +1608,34 @@ impl Application for TaskList {
                 Command::none()
             }
             Message::TaskEdited(id) => {
+                let TaskFields {
+                    due_date,
+                    estimate,
+                    remind_at,
+                } = match self.parse_task_fields() {
+                    Ok(parsed) => parsed,
+                    Err(err) => {
+                        self.mem.task_error = Some(err);
+                        return Command::none();
+                    }
+                };
+                // Other tasks may have changed since a blocker was picked.
+                let editing = TaskRef {
+                    list: self.mem.list_name.clone(),
+                    id,
+                };
+                if let Some(blocker) = self
+                    .mem
+                    .task_blockers
+                    .iter()
+                    .find(|blocker| self.depends_on(blocker, &editing))
+                {
+                    self.mem.task_error = Some(format!(
+                        "\"{}\" already waits on this task.",
+                        self.task_label(blocker)
+                    ));
+                    return Command::none();
+                }
                 if let Some(task) = self
                     .mem
                     .task_entries
@@ -344,16 +1645,28 @@ impl Application for TaskList {
                     task.name = self.mem.task_name.clone();
                     task.description = self.mem.task_desc.clone();
                     task.tags = self.mem.task_tags.clone();
+                    task.due_date = due_date;
+                    task.estimate = estimate;
+                    task.remind_at = remind_at;
+                    task.blocked_by = std::mem::take(&mut self.mem.task_blockers);
 
                     let updated_tasks = Tasks {
                         tasks: self.mem.task_entries.clone(),
+                        meta: ListMeta::default(),
                     };
 
-                    json_handling::write_task(&updated_tasks, self.mem.list_name.clone());
+                    self.saved(json_handling::write_task(
+                        &updated_tasks,
+                        self.mem.list_name.clone(),
+                    ));
+                    self.refresh_list(&self.mem.list_name.clone());
 
                     self.mem.task_name = String::new();
                     self.mem.task_desc = String::new();
                     self.mem.task_tags.clear();
+                    self.mem.task_due = String::new();
+                    self.mem.task_estimate = String::new();
+                    self.mem.task_reminder = String::new();
 
                     match (&self.state, &self.button_pressed) {
                         (State::Edit, Some(ButtonPressed::Edit)) => {
@@ -375,18 +1688,24 @@ impl Application for TaskList {
                     .iter_mut()
                     .find(|entry| entry.id == id)
                 {
-                    task.completed = checked;
+                    task.set_completed(checked, &self.mem.list_meta.statuses());
 
                     let updated_tasks = Tasks {
                         tasks: self.mem.task_entries.clone(),
+                        meta: ListMeta::default(),
                     };
 
-                    json_handling::write_task(&updated_tasks, self.mem.list_name.clone());
+                    self.saved(json_handling::write_task(
+                        &updated_tasks,
+                        self.mem.list_name.clone(),
+                    ));
                 }
+                self.refresh_list(&self.mem.list_name.clone());
                 Command::none()
             }
             Message::NewFileNameChanged(input) => {
                 self.mem.file_name = input;
+                self.mem.new_list_error = None;
                 Command::none()
             }
             Message::CreateNewFileButton => {
@@ -397,20 +1716,32 @@ impl Application for TaskList {
                             Some(ButtonPressed::NewFile)
                         }
                     }
-                    State::Edit | State::Create | State::NewFile | State::DeleteList => {
-                        self.button_pressed = None
-                    }
+                    State::Edit
+                    | State::Create
+                    | State::NewFile
+                    | State::DeleteList
+                    | State::DeleteTask
+                    | State::Settings
+                    | State::CommandPalette
+                    | State::Bulk
+                    | State::Transfer
+                    | State::ManageList
+                    | State::ListDetails
+                    | State::History
+                    | State::TimeReport => self.button_pressed = None,
                 }
                 Command::none()
             }
             Message::CreateNewFile => {
-                self.mem.list_name = self.mem.file_name.clone();
-                json_handling::create_new_task_file(self.mem.list_name.clone());
+                let file = match json_handling::create_new_task_file(self.mem.file_name.clone()) {
+                    Ok(file) => file,
+                    Err(err) => {
+                        self.mem.new_list_error = Some(err);
+                        return Command::none();
+                    }
+                };
                 self.mem.file_name = String::new();
-                self.mem.selected_file = Some(self.mem.list_name.clone());
-
-                let tasks = json_handling::read_tasks(self.mem.list_name.clone()).unwrap();
-                self.mem.task_entries = tasks.tasks;
+                self.mem.new_list_error = None;
 
                 match (&self.state, &self.button_pressed) {
                     (State::NewFile, Some(ButtonPressed::NewFile)) => {
@@ -419,21 +1750,45 @@ impl Application for TaskList {
                     }
                     _ => println!("Attempted to close overlay while in another mode.*"),
                 };
-                Command::none()
+                self.update(Message::FileSelected(file))
             }
             Message::FileSelected(file) => {
                 let test = file.clone().replace(".json", "");
                 self.mem.selected_file = Some(test.clone());
                 self.mem.list_name = test.clone();
+                self.mem.smart_view = None;
+                self.mem.calendar = None;
+                self.mem.agenda = false;
+                self.mem.status_filter = StatusFilter::All;
+                self.refresh_lists();
+                self.remember_last_list();
+                self.mem.selected_tasks.clear();
+                self.mem.selection_anchor = None;
+                self.mem.expanded_tasks.clear();
                 match json_handling::read_tasks(test) {
                     Ok(contents) => {
                         self.mem.task_entries = contents.tasks;
+                        self.mem.list_meta = contents.meta;
+                        Command::none()
+                    }
+                    Err(err) => {
+                        // Nothing of the list that failed to load may be
+                        // written back over it.
+                        self.mem.task_entries.clear();
+                        self.mem.list_meta = ListMeta::default();
+                        self.mem.status = Some(err);
                         Command::none()
                     }
-                    Err(_err) => Command::none(),
                 }
             }
             Message::DeleteListPressed => {
+                self.mem.delete_list_error = None;
+                if !self.settings.confirm_delete_list {
+                    self.state = State::DeleteList;
+                    self.button_pressed = Some(ButtonPressed::DeleteList);
+                    return self.update(Message::DeleteList);
+                }
+
                 match self.state {
                     State::None => {
                         self.button_pressed = {
@@ -441,19 +1796,37 @@ impl Application for TaskList {
                             Some(ButtonPressed::DeleteList)
                         }
                     }
-                    State::Edit | State::Create | State::NewFile | State::DeleteList => {
-                        self.button_pressed = None
-                    }
+                    State::Edit
+                    | State::Create
+                    | State::NewFile
+                    | State::DeleteList
+                    | State::DeleteTask
+                    | State::Settings
+                    | State::CommandPalette
+                    | State::Bulk
+                    | State::Transfer
+                    | State::ManageList
+                    | State::ListDetails
+                    | State::History
+                    | State::TimeReport => self.button_pressed = None,
                 }
                 Command::none()
             }
             Message::DeleteList => {
-                json_handling::delete_task_file(self.mem.list_name.clone());
-                self.mem.selected_file = Some("tasklist".to_string());
-                self.mem.list_name = "tasklist".to_string();
+                if let Err(err) = json_handling::delete_task_file(self.mem.list_name.clone()) {
+                    self.mem.delete_list_error = Some(err);
+                    return Command::none();
+                }
+                // Every list is read again, including those that lost
+                // blocked-by references to the deleted tasks.
+                self.mem.selected_file = Some(self.settings.default_list.clone());
+                self.refresh_lists();
+                self.mem.list_name = self.settings.default_list.clone();
+                self.remember_last_list();
 
                 let tasks = json_handling::read_tasks(self.mem.list_name.clone()).unwrap();
                 self.mem.task_entries = tasks.tasks;
+                self.mem.list_meta = tasks.meta;
 
                 match (&self.state, &self.button_pressed) {
                     (State::DeleteList, Some(ButtonPressed::DeleteList)) => {
@@ -477,313 +1850,4292 @@ impl Application for TaskList {
                 Command::none()
             }
             Message::SearchButtonPressed => {
-                let search_term = self.mem.search_term.to_lowercase();
+                // Words like `status:doing` or `completed:week` filter, the
+                // remaining words are matched as before.
+                let lowered = self.mem.search_term.to_lowercase();
+                let today = today();
+                let mut filters = Vec::new();
+                let mut words = Vec::new();
+                for word in lowered.split_whitespace() {
+                    match SearchFilter::parse(word, today, self.settings.week_start) {
+                        Some(filter) => filters.push(filter),
+                        None => words.push(word),
+                    }
+                }
+                let search_term = words.join(" ");
                 self.mem.task_entries = json_handling::read_tasks(self.mem.list_name.clone())
                     .unwrap()
                     .tasks
                     .iter()
                     .filter(|entry| {
-                        entry.name.to_lowercase().contains(&search_term)
-                            || entry.description.to_lowercase().contains(&search_term)
-                            || entry.tags.join(", ").to_lowercase().contains(&search_term)
+                        let status = entry.status.to_lowercase();
+                        filters.iter().all(|filter| filter.matches(entry))
+                            && (entry.name.to_lowercase().contains(&search_term)
+                                || entry.description.to_lowercase().contains(&search_term)
+                                || entry.tags.join(", ").to_lowercase().contains(&search_term)
+                                || status.contains(&search_term))
                     })
                     .cloned()
                     .collect();
 
                 Command::none()
             }
-        }
-    }
-
-    fn view(&self) -> Element<Self::Message> {
-        let task_entries: Vec<Element<'_, Message>> = self
-            .mem
-            .task_entries
-            .iter()
-            .enumerate()
-            .map(|(index, entry)| {
-                let name = Text::new(format!("Name: {}", &entry.name));
-                let description = Text::new(format!("Description: {}", &entry.description));
-                let tags = Text::new(format!("Tags: {}", &entry.tags.join(", ")));
-
-                let delete_button = Button::new(
-                    Text::new("Delete").horizontal_alignment(alignment::Horizontal::Center),
-                )
-                .width(60)
-                .on_press(Message::ButtonDeletePressed(index))
-                .style(ui_theme::button_theme());
-
-                let edit_button = Button::new(
-                    Text::new("  Edit  ").horizontal_alignment(alignment::Horizontal::Center),
-                )
-                .width(60)
-                .on_press(Message::ButtonEditPressed(index as i32))
-                .style(ui_theme::button_theme());
-
-                let completed_box = Checkbox::new("Completed", entry.completed, move |checked| {
-                    Message::CheckboxChanged(entry.id, checked)
-                })
-                .style(ui_theme::checkbox_theme());
-
-                let button_column = Column::new()
-                    .align_items(Alignment::Center)
-                    .push(edit_button)
-                    .push(delete_button)
-                    .spacing(5);
-
-                let mut task_container = Container::new(
-                    Row::new()
-                        .align_items(Alignment::Center)
-                        .spacing(100)
-                        .push(
-                            Column::new()
-                                .spacing(10)
-                                .push(name)
-                                .push(description)
-                                .push(tags)
-                                .width(Length::Fill),
-                        )
-                        .push(
-                            Column::new().push(
-                                Row::new()
-                                    .push(completed_box)
-                                    .align_items(Alignment::Center)
-                                    .push(button_column)
-                                    .spacing(5),
-                            ),
-                        ),
-                )
-                .style(ui_theme::container_theme())
-                .width(Length::Fill)
-                .padding(5);
-
-                task_container.into()
-            })
-            .collect();
-
-        let mut task_container = Column::new().spacing(10);
-        for task_entry in task_entries {
-            task_container = task_container.push(task_entry);
-        }
+            Message::SettingsButtonPressed => {
+                match self.state {
+                    State::None => {
+                        self.button_pressed = {
+                            self.themes = Self::load_themes();
+                            self.mem.settings_draft = self.settings.clone();
+                            self.mem.font_size_input = self.settings.font_size.to_string();
+                            self.mem.backup_retention_input =
+                                self.settings.backup_retention.to_string();
+                            self.mem.focus_work_input =
+                                self.settings.focus_work_minutes.to_string();
+                            self.mem.focus_break_input =
+                                self.settings.focus_break_minutes.to_string();
+                            self.mem.keymap_inputs = Action::ALL
+                                .iter()
+                                .map(|action| {
+                                    let chords = keymap::chords_for(&self.settings.keymap, *action);
+                                    (*action, chords.join(", "))
+                                })
+                                .collect();
+                            self.state = State::Settings;
+                            Some(ButtonPressed::Settings)
+                        }
+                    }
+                    State::Create
+                    | State::Edit
+                    | State::NewFile
+                    | State::DeleteList
+                    | State::DeleteTask
+                    | State::Settings
+                    | State::CommandPalette
+                    | State::Bulk
+                    | State::Transfer
+                    | State::ManageList
+                    | State::ListDetails
+                    | State::History
+                    | State::TimeReport => self.button_pressed = None,
+                }
+                Command::none()
+            }
+            Message::SettingsThemeChanged(theme) => {
+                self.mem.settings_draft.theme = theme;
+                Command::none()
+            }
+            Message::SettingsDefaultListChanged(list) => {
+                self.mem.settings_draft.default_list = list;
+                Command::none()
+            }
+            Message::SettingsStartupChanged(startup) => {
+                self.mem.settings_draft.startup = startup;
+                Command::none()
+            }
+            Message::SettingsSortOrderChanged(order) => {
+                self.mem.settings_draft.sort_order = order;
+                Command::none()
+            }
+            Message::SettingsConfirmTaskChanged(checked) => {
+                self.mem.settings_draft.confirm_delete_task = checked;
+                Command::none()
+            }
+            Message::SettingsConfirmListChanged(checked) => {
+                self.mem.settings_draft.confirm_delete_list = checked;
+                Command::none()
+            }
+            Message::SettingsDateFormatChanged(format) => {
+                self.mem.settings_draft.date_format = format;
+                Command::none()
+            }
+            Message::SettingsWeekStartChanged(week_start) => {
+                self.mem.settings_draft.week_start = week_start;
+                Command::none()
+            }
+            Message::SettingsFontSizeChanged(input) => {
+                if let Ok(size) = input.parse::<u16>() {
+                    self.mem.settings_draft.font_size = size.clamp(8, 32);
+                }
+                self.mem.font_size_input = input;
+                Command::none()
+            }
+            Message::SettingsBackupRetentionChanged(input) => {
+                if let Ok(count) = input.parse::<usize>() {
+                    self.mem.settings_draft.backup_retention = count;
+                }
+                self.mem.backup_retention_input = input;
+                Command::none()
+            }
+            Message::SettingsFocusWorkChanged(input) => {
+                if let Ok(minutes) = input.parse::<u32>() {
+                    self.mem.settings_draft.focus_work_minutes = minutes.clamp(1, 180);
+                }
+                self.mem.focus_work_input = input;
+                Command::none()
+            }
+            Message::SettingsFocusBreakChanged(input) => {
+                if let Ok(minutes) = input.parse::<u32>() {
+                    self.mem.settings_draft.focus_break_minutes = minutes.clamp(1, 180);
+                }
+                self.mem.focus_break_input = input;
+                Command::none()
+            }
+            Message::SettingsKeymapChanged(action, input) => {
+                let chords: Vec<String> = input
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|chord| !chord.is_empty())
+                    .map(String::from)
+                    .collect();
+                self.mem.settings_draft.keymap.insert(action, chords);
+                self.mem.keymap_inputs.insert(action, input);
+                Command::none()
+            }
+            Message::SettingsSaved => {
+                // Window state is tracked separately, keep whatever is on disk.
+                let stored = json_handling::read_settings().unwrap_or_default();
+                let mut settings = self.mem.settings_draft.clone();
+                settings.run = stored.run;
+                settings.x = stored.x;
+                settings.y = stored.y;
+                settings.width = stored.width;
+                settings.height = stored.height;
+                settings.maximized = stored.maximized;
+                settings.last_list = self.mem.list_name.clone();
+                json_handling::write_settings(&settings);
+                self.settings = settings;
 
-        let task_scrollbar = Scrollable::new(task_container)
-            .style(ui_theme::scrollable_theme())
-            .width(Length::Fill)
-            .height(Length::Fill);
+                match (&self.state, &self.button_pressed) {
+                    (State::Settings, Some(ButtonPressed::Settings)) => {
+                        self.state = State::None;
+                        self.button_pressed = None;
+                    }
+                    _ => println!("Attempted to close overlay while in another mode."),
+                };
+                Command::none()
+            }
+            Message::KeyPressed(key_code, modifiers) => {
+                if let State::CommandPalette = self.state {
+                    let count =
+                        command_palette::matches(&self.mem.palette_items, &self.mem.palette_query)
+                            .len();
+                    match key_code {
+                        KeyCode::Down if count > 0 => {
+                            self.mem.palette_selected = (self.mem.palette_selected + 1) % count;
+                        }
+                        KeyCode::Up if count > 0 => {
+                            self.mem.palette_selected =
+                                (self.mem.palette_selected + count - 1) % count;
+                        }
+                        _ => {}
+                    }
+                    return Command::none();
+                }
+                // The task list is hidden while focusing. Text inputs let
+                // Tab and the arrow keys through, they are not meant for the
+                // task list while typing.
+                if !matches!(self.state, State::None)
+                    || self.mem.focus.is_some()
+                    || self.mem.input_focused
+                {
+                    return Command::none();
+                }
 
-        let underlay = match self.state {
-            State::None => {
-                let files_result = json_handling::get_files();
-                let pick_list = match files_result {
-                    Ok(file_names) => pick_list(
-                        file_names[..].to_vec(),
-                        self.mem.selected_file.clone(),
-                        Message::FileSelected,
-                    )
-                    .width(150),
+                match keymap::action_for(&self.settings.keymap, key_code, modifiers) {
+                    Some(action) => self.run_action(action),
+                    None => Command::none(),
+                }
+            }
+            Message::ModifiersChanged(modifiers) => {
+                self.mem.modifiers = modifiers;
+                Command::none()
+            }
+            Message::CheckInputFocus => {
+                // The lookup only answers when some widget has focus.
+                self.mem.input_focused = false;
+                Command::widget(focusable::find_focused()).map(Message::InputFocused)
+            }
+            Message::InputFocused(id) => {
+                self.mem.input_focused = id == search_input_id().into();
+                Command::none()
+            }
+            Message::SelectAll => {
+                // The visible entries are already narrowed down by the search.
+                self.mem.selected_tasks = self
+                    .visible_entries()
+                    .iter()
+                    .map(|entry| entry.id)
+                    .collect();
+                Command::none()
+            }
+            Message::ClearSelection => {
+                self.mem.selected_tasks.clear();
+                self.mem.selection_anchor = None;
+                Command::none()
+            }
+            Message::BulkPressed => {
+                match self.state {
+                    State::None if !self.mem.selected_tasks.is_empty() => {
+                        self.button_pressed = {
+                            self.mem.bulk_tag = String::new();
+                            self.mem.bulk_due_date = String::new();
+                            self.mem.bulk_target = None;
+                            self.mem.bulk_error = None;
+                            self.state = State::Bulk;
+                            Some(ButtonPressed::Bulk)
+                        }
+                    }
+                    State::None
+                    | State::Create
+                    | State::Edit
+                    | State::NewFile
+                    | State::DeleteList
+                    | State::DeleteTask
+                    | State::Settings
+                    | State::CommandPalette
+                    | State::Bulk
+                    | State::Transfer
+                    | State::ManageList
+                    | State::ListDetails
+                    | State::History
+                    | State::TimeReport => self.button_pressed = None,
+                }
+                Command::none()
+            }
+            Message::BulkSetCompleted(completed) => {
+                let statuses = self.mem.list_meta.statuses();
+                self.update_selected(|task| task.set_completed(completed, &statuses));
+                Command::none()
+            }
+            Message::BulkDelete => {
+                let ids: Vec<i32> = self.mem.selected_tasks.iter().copied().collect();
+                let changed = self
+                    .saved(json_handling::delete_tasks(
+                        &ids,
+                        self.mem.list_name.clone(),
+                    ))
+                    .unwrap_or_default();
+                self.reload_after_bulk(&changed);
+                Command::none()
+            }
+            Message::BulkTagChanged(input) => {
+                self.mem.bulk_tag = input;
+                Command::none()
+            }
+            Message::BulkAddTag => {
+                let tag = self.mem.bulk_tag.trim().to_string();
+                if tag.is_empty() {
+                    return Command::none();
+                }
+                self.update_selected(|task| {
+                    let mut tags = split_tags(&task.tags);
+                    if !tags.contains(&tag) {
+                        tags.push(tag.clone());
+                    }
+                    task.tags = tags;
+                });
+                Command::none()
+            }
+            Message::BulkRemoveTag => {
+                let tag = self.mem.bulk_tag.trim().to_string();
+                self.update_selected(|task| {
+                    let mut tags = split_tags(&task.tags);
+                    tags.retain(|other| *other != tag);
+                    task.tags = tags;
+                });
+                Command::none()
+            }
+            Message::BulkPrioritySelected(priority) => {
+                self.update_selected(|task| task.priority = priority);
+                Command::none()
+            }
+            Message::BulkDueDateChanged(input) => {
+                self.mem.bulk_due_date = input;
+                Command::none()
+            }
+            Message::BulkSetDueDate => {
+                let pattern = self.settings.date_format.pattern();
+                match NaiveDate::parse_from_str(self.mem.bulk_due_date.trim(), pattern) {
+                    Ok(date) => self.update_selected(|task| task.due_date = Some(date)),
                     Err(_) => {
-                        PickList::new(&[][..], None, Message::FileSelected) // Empty pick_list in case of error
+                        self.mem.bulk_error = Some(format!(
+                            "Invalid date, expected {}",
+                            self.settings.date_format
+                        ))
                     }
+                }
+                Command::none()
+            }
+            Message::BulkClearDueDate => {
+                self.update_selected(|task| task.due_date = None);
+                Command::none()
+            }
+            Message::BulkTargetSelected(list) => {
+                self.mem.bulk_target = Some(list);
+                Command::none()
+            }
+            Message::BulkMove | Message::BulkCopy => {
+                let Some(target) = self.mem.bulk_target.clone() else {
+                    self.mem.bulk_error = Some("Choose a list first".to_string());
+                    return Command::none();
                 };
-
-                let button = |label, message| {
-                    button(text(label).horizontal_alignment(alignment::Horizontal::Center))
-                        .style(ui_theme::button_theme())
-                        .width(90)
-                        .on_press(message)
+                let entries: Vec<TaskEntry> = self
+                    .mem
+                    .task_entries
+                    .iter()
+                    .filter(|entry| self.mem.selected_tasks.contains(&entry.id))
+                    .cloned()
+                    .collect();
+                let new_ids = match json_handling::copy_tasks(
+                    &entries,
+                    &target,
+                    matches!(message, Message::BulkMove),
+                ) {
+                    Ok(new_ids) => new_ids,
+                    Err(err) => {
+                        self.mem.bulk_error = Some(err);
+                        return Command::none();
+                    }
                 };
 
-                let search_bar = TextInput::new("Search", self.mem.search_term.as_str())
-                    .on_input(Message::SearchChanged)
-                    .on_submit(Message::SearchButtonPressed);
-                let search_button = button("Search", Message::SearchButtonPressed);
-
-                container(
-                    column![
-                        row![
-                            button("New List", Message::CreateNewFileButton),
-                            pick_list.style(ui_theme::pick_list_theme()),
-                            button("Delete List", Message::DeleteListPressed),
-                        ]
-                        .spacing(5)
-                        .padding(2),
-                        task_scrollbar, // Ensure the task scrollbar is added after the other elements
-                        row![
-                            button("New Task", Message::ButtonCreatePressed),
-                            search_bar.style(ui_theme::text_input_theme()).width(150),
-                            search_button,
-                        ]
-                        .spacing(5)
-                        .padding(2),
-                    ]
-                    .align_items(Alignment::Center)
-                    .spacing(10)
-                    .padding(10),
-                )
-                .width(Length::Fill)
-                .height(Length::Fill)
-                .center_x()
+                let mut changed = vec![target.clone()];
+                if let Message::BulkMove = message {
+                    let ids: Vec<i32> = entries.iter().map(|entry| entry.id).collect();
+                    changed.extend(self.retarget_moved(&ids, &target, &new_ids));
+                    let deleted = json_handling::delete_tasks(&ids, self.mem.list_name.clone());
+                    changed.extend(self.saved(deleted).unwrap_or_default());
+                }
+                self.reload_after_bulk(&changed);
+                Command::none()
             }
-            _ => container(task_scrollbar)
-                .padding(10)
-                .width(Length::Fill)
-                .height(Length::Fill),
-        };
-
-        let overlay = self.button_pressed.as_ref().map(|button_pressed| {
-            let head_string = match button_pressed {
-                ButtonPressed::Create => "New Task",
-                ButtonPressed::Edit => "Edit Task",
-                ButtonPressed::NewFile => "New List",
-                ButtonPressed::DeleteList => "Delete List",
-            };
-
-            let body_string = match button_pressed {
-                ButtonPressed::Create => {
-                    let task_name_input = text_input("Name", &self.mem.task_name)
-                        .on_input(Message::TaskNameChanged)
-                        .on_submit(Message::TaskSubmitted)
-                        .style(ui_theme::text_input_theme())
-                        .width(Length::Fill);
-
-                    let task_description_input = text_input("Description", &self.mem.task_desc)
-                        .on_input(Message::TaskDescEdited)
-                        .on_submit(Message::TaskSubmitted)
-                        .style(ui_theme::text_input_theme())
-                        .width(Length::Fill);
-
-                    let tags_input = text_input("Tags", &self.mem.task_tags.join(","))
-                        .on_input(Message::TagsChanged)
-                        .on_submit(Message::TaskSubmitted)
-                        .style(ui_theme::text_input_theme())
-                        .width(Length::Fill);
-
-                    let ok_button =
-                        button(text("Ok").horizontal_alignment(alignment::Horizontal::Center))
-                            .on_press(Message::TaskSubmitted)
-                            .style(ui_theme::button_theme())
-                            .width(Length::Fill);
-
-                    let cancel_button =
-                        button(text("Cancel").horizontal_alignment(alignment::Horizontal::Center))
-                            .on_press(Message::CloseOverlay)
-                            .style(ui_theme::button_theme())
-                            .width(Length::Fill);
+            Message::ToggleSidebar => {
+                self.settings.sidebar_collapsed = !self.settings.sidebar_collapsed;
+                let mut stored = json_handling::read_settings().unwrap_or_default();
+                stored.sidebar_collapsed = self.settings.sidebar_collapsed;
+                json_handling::write_settings(&stored);
+                Command::none()
+            }
+            Message::ListPressed(file) => {
+                self.mem.dragging_list = Some(file);
+                Command::none()
+            }
+            Message::ListReleased(file) => match self.mem.dragging_list.take() {
+                Some(dragged) if dragged == file => self.update(Message::FileSelected(file)),
+                Some(dragged) => {
+                    // Dropping onto another list files it next to that list.
+                    let folder = self
+                        .mem
+                        .lists
+                        .iter()
+                        .find(|list| list.file == file)
+                        .map(|list| list.folder.clone())
+                        .unwrap_or_default();
+                    self.move_list(&dragged, &folder);
+                    Command::none()
+                }
+                None => Command::none(),
+            },
+            Message::FolderPressed(folder) => {
+                self.mem.pressed_folder = Some(folder);
+                Command::none()
+            }
+            Message::FolderReleased(folder) => {
+                if let Some(dragged) = self.mem.dragging_list.take() {
+                    self.move_list(&dragged, &folder);
+                } else if self.mem.pressed_folder.take().as_ref() == Some(&folder) {
+                    if !self.settings.collapsed_folders.remove(&folder) {
+                        self.settings.collapsed_folders.insert(folder);
+                    }
+                    let mut stored = json_handling::read_settings().unwrap_or_default();
+                    stored.collapsed_folders = self.settings.collapsed_folders.clone();
+                    json_handling::write_settings(&stored);
+                }
+                Command::none()
+            }
+            Message::DetailsFolderChanged(input) => {
+                self.mem.details_draft.folder = input;
+                Command::none()
+            }
+            Message::DetailsStatusesChanged(input) => {
+                self.mem.statuses_input = input;
+                self.mem.list_error = None;
+                Command::none()
+            }
+            Message::DetailsDoneStatusSelected(name) => {
+                self.mem.done_status_choice = Some(name);
+                Command::none()
+            }
+            Message::StatusSelected(id, name) => {
+                let statuses = self.mem.list_meta.statuses();
+                let Some(status) = statuses.iter().find(|status| status.name == name) else {
+                    return Command::none();
+                };
+                if let Some(task) = self
+                    .mem
+                    .task_entries
+                    .iter_mut()
+                    .find(|entry| entry.id == id)
+                {
+                    task.set_status(status);
+                    let task = task.clone();
+                    self.saved(json_handling::write_task(
+                        &Tasks {
+                            tasks: vec![task],
+                            meta: ListMeta::default(),
+                        },
+                        self.mem.list_name.clone(),
+                    ));
+                }
+                self.refresh_list(&self.mem.list_name.clone());
+                Command::none()
+            }
+            Message::StatusFilterSelected(filter) => {
+                self.mem.status_filter = filter;
+                Command::none()
+            }
+            Message::ToggleBoard => {
+                let mut meta = self.mem.list_meta.clone();
+                meta.view = match meta.view {
+                    ListView::List => ListView::Board,
+                    ListView::Board => ListView::List,
+                };
+                match json_handling::write_list_meta(&self.mem.list_name, &meta) {
+                    Ok(()) => self.mem.list_meta = meta,
+                    Err(err) => self.mem.status = Some(err),
+                }
+                Command::none()
+            }
+            Message::BoardDropped(target) => {
+                let Some(id) = self.mem.dragging_task.take() else {
+                    return Command::none();
+                };
+                let statuses = self.mem.list_meta.statuses();
+                if let Some(task) = self
+                    .mem
+                    .task_entries
+                    .iter_mut()
+                    .find(|entry| entry.id == id)
+                {
+                    if board_column(task, &statuses) == target {
+                        return Command::none();
+                    }
+                    task.set_status(&statuses[target]);
+                    let task = task.clone();
+                    self.saved(json_handling::write_task(
+                        &Tasks {
+                            tasks: vec![task],
+                            meta: ListMeta::default(),
+                        },
+                        self.mem.list_name.clone(),
+                    ));
+                }
+                self.refresh_list(&self.mem.list_name.clone());
+                Command::none()
+            }
+            Message::SmartViewSelected(view) => {
+                self.refresh_lists();
+                self.mem.smart_view = Some(view);
+                self.mem.calendar = None;
+                self.mem.agenda = false;
+                Command::none()
+            }
+            Message::AgendaOpened => {
+                self.refresh_lists();
+                self.mem.smart_view = None;
+                self.mem.calendar = None;
+                self.mem.agenda = true;
+                Command::none()
+            }
+            Message::ListedTaskEditPressed(list, id) => {
+                // The owning list is loaded behind the view so the Edit card
+                // writes back to it.
+                if list != self.mem.list_name {
+                    match json_handling::read_tasks(list.clone()) {
+                        Ok(tasks) => {
+                            self.mem.list_name = list.clone();
+                            self.mem.selected_file = Some(list);
+                            self.mem.task_entries = tasks.tasks;
+                            self.mem.list_meta = tasks.meta;
+                            self.mem.selected_tasks.clear();
+                            self.mem.selection_anchor = None;
+                            self.mem.status_filter = StatusFilter::All;
+                        }
+                        Err(err) => {
+                            self.mem.status = Some(err);
+                            return Command::none();
+                        }
+                    }
+                }
+                self.update(Message::ButtonEditPressed(id))
+            }
+            Message::CalendarOpened => {
+                self.refresh_lists();
+                self.mem.smart_view = None;
+                self.mem.agenda = false;
+                self.mem.calendar = Some(self.mem.calendar.unwrap_or(CalendarSpan::Month));
+                Command::none()
+            }
+            Message::CalendarSpanSelected(span) => {
+                self.mem.calendar = Some(span);
+                Command::none()
+            }
+            Message::CalendarStepped(forward) => {
+                if let Some(span) = self.mem.calendar {
+                    self.mem.calendar_anchor = span.step(self.mem.calendar_anchor, forward);
+                }
+                Command::none()
+            }
+            Message::CalendarToday => {
+                self.mem.calendar_anchor = today();
+                Command::none()
+            }
+            Message::CalendarTaskPressed(list, id) => {
+                self.mem.calendar_drag = Some((list, id));
+                Command::none()
+            }
+            Message::CalendarDayPressed(day) => {
+                self.mem.pressed_day = Some(day);
+                Command::none()
+            }
+            Message::CalendarDayReleased(day) => {
+                if let Some((list, id)) = self.mem.calendar_drag.take() {
+                    let due_date = self
+                        .mem
+                        .all_tasks
+                        .iter()
+                        .find(|task| task.list == list && task.entry.id == id)
+                        .and_then(|task| task.entry.due_date);
+                    if due_date == Some(day) {
+                        return self.update(Message::ListedTaskOpened(list, id));
+                    }
+                    self.reschedule(&list, id, day);
+                } else if self.mem.pressed_day.take() == Some(day) {
+                    // Clicking a day starts a task in the open list due that day.
+                    self.mem.task_due = day.format(self.settings.date_format.pattern()).to_string();
+                    return self.update(Message::ButtonCreatePressed);
+                }
+                Command::none()
+            }
+            Message::ListedTaskToggled(list, id, completed) => {
+                if list == self.mem.list_name {
+                    return self.update(Message::CheckboxChanged(id, completed));
+                }
+                match json_handling::read_tasks(list.clone()) {
+                    Ok(mut tasks) => {
+                        let statuses = tasks.meta.statuses();
+                        if let Some(task) = tasks.tasks.iter_mut().find(|task| task.id == id) {
+                            task.set_completed(completed, &statuses);
+                            self.saved(json_handling::write_task(&tasks, list.clone()));
+                        }
+                    }
+                    Err(err) => println!("{}", err),
+                }
+                self.refresh_list(&list);
+                Command::none()
+            }
+            Message::ListedTaskOpened(list, id) => {
+                let command = self.update(Message::FileSelected(list));
+                self.mem.focused_task = Some(id);
+                Command::batch(vec![command, self.move_focus(0)])
+            }
+            Message::ListDetailsPressed => {
+                match self.state {
+                    State::None => {
+                        self.button_pressed = {
+                            self.mem.details_draft = self.mem.list_meta.clone();
+                            let statuses = self.mem.list_meta.statuses();
+                            self.mem.statuses_input = statuses
+                                .iter()
+                                .map(|status| status.name.as_str())
+                                .collect::<Vec<_>>()
+                                .join(", ");
+                            self.mem.done_status_choice = statuses
+                                .into_iter()
+                                .find(|status| status.done)
+                                .map(|status| status.name);
+                            self.mem.list_error = None;
+                            self.state = State::ListDetails;
+                            Some(ButtonPressed::ListDetails)
+                        }
+                    }
+                    State::Create
+                    | State::Edit
+                    | State::NewFile
+                    | State::DeleteList
+                    | State::DeleteTask
+                    | State::Settings
+                    | State::CommandPalette
+                    | State::Bulk
+                    | State::Transfer
+                    | State::ManageList
+                    | State::ListDetails
+                    | State::History
+                    | State::TimeReport => self.button_pressed = None,
+                }
+                Command::none()
+            }
+            Message::DetailsDescriptionChanged(input) => {
+                self.mem.details_draft.description = input;
+                Command::none()
+            }
+            Message::DetailsColorSelected(color) => {
+                self.mem.details_draft.color = color;
+                Command::none()
+            }
+            Message::DetailsIconSelected(icon) => {
+                self.mem.details_draft.icon = icon;
+                Command::none()
+            }
+            Message::DetailsSortSelected(choice) => {
+                self.mem.details_draft.sort_order = match choice {
+                    ListSortChoice::Settings => None,
+                    ListSortChoice::Fixed(order) => Some(order),
+                };
+                Command::none()
+            }
+            Message::DetailsPinnedToggled(pinned) => {
+                self.mem.details_draft.pinned = if !pinned {
+                    None
+                } else {
+                    self.mem.list_meta.pinned.or_else(|| {
+                        let next = self
+                            .mem
+                            .lists
+                            .iter()
+                            .filter_map(|list| list.pinned)
+                            .map(|slot| slot + 1)
+                            .max()
+                            .unwrap_or(0);
+                        Some(next)
+                    })
+                };
+                Command::none()
+            }
+            Message::SaveListDetails => {
+                let mut meta = self.mem.details_draft.clone();
+                meta.description = meta.description.trim().to_string();
+                meta.folder = json_handling::normalize_folder(&meta.folder);
+                let names = match parse_statuses(&self.mem.statuses_input) {
+                    Ok(names) => names,
+                    Err(err) => {
+                        self.mem.list_error = Some(err);
+                        return Command::none();
+                    }
+                };
+                let done = names
+                    .iter()
+                    .position(|name| Some(name) == self.mem.done_status_choice.as_ref())
+                    .unwrap_or(names.len().saturating_sub(1));
+                meta.statuses = names
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, name)| Status {
+                        name,
+                        done: index == done,
+                    })
+                    .collect();
+                // The name is only changed through the Manage card.
+                meta.name = self.mem.list_meta.name.clone();
+                if let Err(err) = json_handling::write_list_meta(&self.mem.list_name, &meta) {
+                    self.mem.list_error = Some(err);
+                    return Command::none();
+                }
+                self.mem.list_meta = meta;
+                // Tasks in a removed status are moved when the list is read.
+                self.mem.task_entries = json_handling::read_tasks(self.mem.list_name.clone())
+                    .map(|tasks| tasks.tasks)
+                    .unwrap_or_default();
+                self.mem.status_filter = StatusFilter::All;
+                self.refresh_list(&self.mem.list_name.clone());
+
+                match (&self.state, &self.button_pressed) {
+                    (State::ListDetails, Some(ButtonPressed::ListDetails)) => {
+                        self.state = State::None;
+                        self.button_pressed = None;
+                    }
+                    _ => println!("Attempted to close overlay while in another mode."),
+                };
+                Command::none()
+            }
+            Message::ManageListPressed => {
+                match self.state {
+                    State::None => {
+                        self.button_pressed = {
+                            let name = json_handling::list_display_name(&self.mem.list_name);
+                            self.mem.duplicate_input = format!("{} copy", name);
+                            self.mem.rename_input = name;
+                            self.mem.duplicate_reset_completed = false;
+                            self.mem.archived_choice = None;
+                            self.mem.archived_lists = json_handling::get_archived_lists();
+                            self.mem.list_error = None;
+                            self.state = State::ManageList;
+                            Some(ButtonPressed::ManageList)
+                        }
+                    }
+                    State::Create
+                    | State::Edit
+                    | State::NewFile
+                    | State::DeleteList
+                    | State::DeleteTask
+                    | State::Settings
+                    | State::CommandPalette
+                    | State::Bulk
+                    | State::Transfer
+                    | State::ManageList
+                    | State::ListDetails
+                    | State::History
+                    | State::TimeReport => self.button_pressed = None,
+                }
+                Command::none()
+            }
+            Message::RenameInputChanged(input) => {
+                self.mem.rename_input = input;
+                Command::none()
+            }
+            Message::RenameList => {
+                let old_name = self.mem.list_name.clone();
+                match json_handling::rename_task_file(&old_name, &self.mem.rename_input) {
+                    Ok(()) => {
+                        self.close_manage_list_card();
+                        self.update(Message::FileSelected(old_name))
+                    }
+                    Err(err) => {
+                        self.mem.list_error = Some(err);
+                        Command::none()
+                    }
+                }
+            }
+            Message::DuplicateInputChanged(input) => {
+                self.mem.duplicate_input = input;
+                Command::none()
+            }
+            Message::DuplicateResetToggled(checked) => {
+                self.mem.duplicate_reset_completed = checked;
+                Command::none()
+            }
+            Message::DuplicateList => {
+                match json_handling::duplicate_task_file(
+                    &self.mem.list_name,
+                    &self.mem.duplicate_input,
+                    self.mem.duplicate_reset_completed,
+                ) {
+                    Ok(file) => {
+                        self.close_manage_list_card();
+                        self.update(Message::FileSelected(file))
+                    }
+                    Err(err) => {
+                        self.mem.list_error = Some(err);
+                        Command::none()
+                    }
+                }
+            }
+            Message::ArchiveList => {
+                if self
+                    .mem
+                    .lists
+                    .iter()
+                    .all(|list| list.file == self.mem.list_name)
+                {
+                    self.mem.list_error = Some("The only list cannot be archived".to_string());
+                    return Command::none();
+                }
+                match json_handling::archive_task_file(&self.mem.list_name) {
+                    Ok(()) => {
+                        self.mem.archived_lists = json_handling::get_archived_lists();
+                        self.close_manage_list_card();
+                        self.refresh_lists();
+                        let next = self.fallback_list(&self.mem.list_name);
+                        self.update(Message::FileSelected(next))
+                    }
+                    Err(err) => {
+                        self.mem.list_error = Some(err);
+                        Command::none()
+                    }
+                }
+            }
+            Message::ArchivedListSelected(list) => {
+                self.mem.archived_choice = Some(list);
+                Command::none()
+            }
+            Message::RestoreList => {
+                let Some(list) = self.mem.archived_choice.clone() else {
+                    self.mem.list_error = Some("Choose an archived list first".to_string());
+                    return Command::none();
+                };
+                match json_handling::restore_task_file(&list) {
+                    Ok(()) => {
+                        self.mem.archived_lists = json_handling::get_archived_lists();
+                        self.close_manage_list_card();
+                        self.update(Message::FileSelected(list))
+                    }
+                    Err(err) => {
+                        self.mem.list_error = Some(err);
+                        Command::none()
+                    }
+                }
+            }
+            Message::ButtonMovePressed(id) | Message::ButtonCopyPressed(id) => {
+                match self.state {
+                    State::None => {
+                        self.button_pressed = {
+                            self.mem.transfer_task_id = Some(id);
+                            self.mem.transfer_keep_source =
+                                matches!(message, Message::ButtonCopyPressed(_));
+                            self.mem.transfer_target = None;
+                            self.mem.transfer_error = None;
+                            self.state = State::Transfer;
+                            Some(ButtonPressed::Transfer)
+                        }
+                    }
+                    State::Create
+                    | State::Edit
+                    | State::NewFile
+                    | State::DeleteList
+                    | State::DeleteTask
+                    | State::Settings
+                    | State::CommandPalette
+                    | State::Bulk
+                    | State::Transfer
+                    | State::ManageList
+                    | State::ListDetails
+                    | State::History
+                    | State::TimeReport => self.button_pressed = None,
+                }
+                Command::none()
+            }
+            Message::TransferTargetSelected(list) => {
+                self.mem.transfer_target = Some(list);
+                Command::none()
+            }
+            Message::TransferTask => {
+                let Some(target) = self.mem.transfer_target.clone() else {
+                    self.mem.transfer_error = Some("Choose a list first".to_string());
+                    return Command::none();
+                };
+                let Some(task) = self
+                    .mem
+                    .transfer_task_id
+                    .and_then(|id| self.mem.task_entries.iter().find(|entry| entry.id == id))
+                    .cloned()
+                else {
+                    return Command::none();
+                };
+
+                let new_ids = match json_handling::copy_tasks(
+                    std::slice::from_ref(&task),
+                    &target,
+                    !self.mem.transfer_keep_source,
+                ) {
+                    Ok(new_ids) => new_ids,
+                    Err(err) => {
+                        self.mem.transfer_error = Some(err);
+                        return Command::none();
+                    }
+                };
+                let mut changed = vec![target.clone()];
+                if !self.mem.transfer_keep_source {
+                    changed.extend(self.retarget_moved(&[task.id], &target, &new_ids));
+                    let deleted =
+                        json_handling::delete_tasks(&[task.id], self.mem.list_name.clone());
+                    changed.extend(self.saved(deleted).unwrap_or_default());
+                    self.mem.task_entries = json_handling::read_tasks(self.mem.list_name.clone())
+                        .unwrap_or_default()
+                        .tasks;
+                    self.mem.selected_tasks.clear();
+                    self.mem.focused_task = None;
+                }
+                self.refresh_changed(&changed);
+
+                match (&self.state, &self.button_pressed) {
+                    (State::Transfer, Some(ButtonPressed::Transfer)) => {
+                        self.mem.transfer_task_id = None;
+                        self.state = State::None;
+                        self.button_pressed = None;
+                    }
+                    _ => println!("Attempted to close overlay while in another mode."),
+                };
+                Command::none()
+            }
+            Message::ButtonHistoryPressed(id) => {
+                match self.state {
+                    State::None => {
+                        self.button_pressed = {
+                            self.mem.history_task_id = Some(id);
+                            self.load_history();
+                            self.state = State::History;
+                            Some(ButtonPressed::History)
+                        }
+                    }
+                    State::Create
+                    | State::Edit
+                    | State::NewFile
+                    | State::DeleteList
+                    | State::DeleteTask
+                    | State::Settings
+                    | State::CommandPalette
+                    | State::Bulk
+                    | State::Transfer
+                    | State::ManageList
+                    | State::ListDetails
+                    | State::History
+                    | State::TimeReport => self.button_pressed = None,
+                }
+                Command::none()
+            }
+            Message::RevertChange(index) => {
+                let (Some(change), Some(id)) = (
+                    self.mem.history.get(index).cloned(),
+                    self.mem.history_task_id,
+                ) else {
+                    return Command::none();
+                };
+                let statuses = self.mem.list_meta.statuses();
+                let Some(task) = self
+                    .mem
+                    .task_entries
+                    .iter_mut()
+                    .find(|entry| entry.id == id)
+                else {
+                    self.mem.history_error = Some("The task is no longer in this list".to_string());
+                    return Command::none();
+                };
+                if let Err(err) = history::apply(task, change.field, &change.old, &statuses) {
+                    self.mem.history_error = Some(err);
+                    return Command::none();
+                }
+                let task = task.clone();
+                self.saved(json_handling::write_task(
+                    &Tasks {
+                        tasks: vec![task],
+                        meta: ListMeta::default(),
+                    },
+                    self.mem.list_name.clone(),
+                ));
+                self.refresh_list(&self.mem.list_name.clone());
+                self.load_history();
+                Command::none()
+            }
+            Message::TimerToggled(list, id) => {
+                self.toggle_timer(&list, id);
+                Command::none()
+            }
+            // Redraws the running timer in the title and moves the focus
+            // session along.
+            Message::Tick => {
+                let now = Local::now();
+                match &self.mem.focus {
+                    Some(focus) if focus.is_over(now) => self.finish_focus_period(now),
+                    _ => Command::none(),
+                }
+            }
+            Message::FocusStarted(list, id) => {
+                let Some(task) = self
+                    .mem
+                    .all_tasks
+                    .iter()
+                    .find(|task| task.list == list && task.entry.id == id)
+                else {
+                    return Command::none();
+                };
+                let focus = Focus::new(
+                    TaskRef { list, id },
+                    task.entry.name.clone(),
+                    chrono::Duration::minutes(i64::from(self.settings.focus_work_minutes)),
+                    Local::now(),
+                );
+                // The pomodoro keeps the time, a running timer would count it twice.
+                if let Some((task, _)) = self.running_timer() {
+                    let (list, id) = (task.list.clone(), task.entry.id);
+                    self.toggle_timer(&list, id);
+                }
+                self.mem.focus = Some(focus);
+
+                let mut commands = Vec::new();
+                if self.mem.window_geometry.maximized {
+                    commands.push(window::maximize(false));
+                }
+                commands.push(window::resize(iced::Size::new(
+                    focus::FOCUS_WINDOW_WIDTH,
+                    focus::FOCUS_WINDOW_HEIGHT,
+                )));
+                Command::batch(commands)
+            }
+            Message::FocusPauseToggled => {
+                if let Some(focus) = &mut self.mem.focus {
+                    focus.toggle_pause(Local::now());
+                }
+                Command::none()
+            }
+            Message::FocusSkipped => {
+                let now = Local::now();
+                if let Some(focus) = &mut self.mem.focus {
+                    focus.advance(
+                        chrono::Duration::minutes(i64::from(self.settings.focus_work_minutes)),
+                        chrono::Duration::minutes(i64::from(self.settings.focus_break_minutes)),
+                        now,
+                    );
+                }
+                Command::none()
+            }
+            Message::FocusCompleteToggled(checked) => {
+                if let Some(focus) = &mut self.mem.focus {
+                    focus.complete_at_end = checked;
+                }
+                Command::none()
+            }
+            Message::FocusStopped => self.end_focus(),
+            Message::ToggleTaskDetails(id) => {
+                if !self.mem.expanded_tasks.remove(&id) {
+                    self.mem.expanded_tasks.insert(id);
+                }
+                Command::none()
+            }
+            Message::LinkClicked(url) => {
+                if !markdown::is_openable(&url) {
+                    self.mem.status =
+                        Some(format!("Not opening {}, only web and mail links open", url));
+                } else if let Err(err) = open::that(&url) {
+                    self.mem.status = Some(format!("Failed to open {}: {}", url, err));
+                }
+                Command::none()
+            }
+            Message::ReminderCheck => self.check_reminders(),
+            Message::ReminderAnswered(task, action) => {
+                if action.is_some() {
+                    self.mem.notifications.close(&task);
+                }
+                match action {
+                    Some(ReminderAction::Snooze) => {
+                        let remind_at =
+                            Local::now() + chrono::Duration::minutes(reminders::SNOOZE_MINUTES);
+                        if let Some(mut entry) =
+                            self.listed_task(&task).map(|listed| listed.entry.clone())
+                        {
+                            entry.remind_at = Some(remind_at);
+                            self.saved(json_handling::write_task(
+                                &Tasks {
+                                    tasks: vec![entry],
+                                    meta: ListMeta::default(),
+                                },
+                                task.list.clone(),
+                            ));
+                            self.refresh_list(&task.list);
+                        }
+                    }
+                    Some(ReminderAction::Done) => {
+                        let command = self.update(Message::ListedTaskToggled(
+                            task.list.clone(),
+                            task.id,
+                            true,
+                        ));
+                        self.mem.reminders.retain(|other| *other != task);
+                        return command;
+                    }
+                    // Dismissed notifications stay in the reminder bar.
+                    None => return Command::none(),
+                }
+                self.mem.reminders.retain(|other| *other != task);
+                Command::none()
+            }
+            Message::ReminderDismissed(task) => {
+                self.mem.notifications.close(&task);
+                self.mem.reminders.retain(|other| *other != task);
+                Command::none()
+            }
+            Message::TimeReportPressed => {
+                match self.state {
+                    State::None => {
+                        self.button_pressed = {
+                            let pattern = self.settings.date_format.pattern();
+                            let today = today();
+                            self.mem.report_from =
+                                calendar::start_of_week(today, self.settings.week_start)
+                                    .format(pattern)
+                                    .to_string();
+                            self.mem.report_to = today.format(pattern).to_string();
+                            self.mem.report_error = None;
+                            self.state = State::TimeReport;
+                            Some(ButtonPressed::TimeReport)
+                        }
+                    }
+                    State::Create
+                    | State::Edit
+                    | State::NewFile
+                    | State::DeleteList
+                    | State::DeleteTask
+                    | State::Settings
+                    | State::CommandPalette
+                    | State::Bulk
+                    | State::Transfer
+                    | State::ManageList
+                    | State::ListDetails
+                    | State::History
+                    | State::TimeReport => self.button_pressed = None,
+                }
+                Command::none()
+            }
+            Message::ReportFromChanged(input) => {
+                self.mem.report_from = input;
+                self.mem.report_error = None;
+                Command::none()
+            }
+            Message::ReportToChanged(input) => {
+                self.mem.report_to = input;
+                self.mem.report_error = None;
+                Command::none()
+            }
+            Message::ReportGroupSelected(group) => {
+                self.mem.report_group = group;
+                Command::none()
+            }
+            Message::ExportTimeReport => {
+                match self.report_range().and_then(|(from, to)| {
+                    let rows = time_tracking::report(
+                        &self.mem.all_tasks,
+                        self.mem.report_group,
+                        from,
+                        to,
+                        Local::now(),
+                    );
+                    json_handling::export_time_report(
+                        &format!("time-report-{}-{}", from, to),
+                        &time_tracking::report_csv(self.mem.report_group, &rows),
+                    )
+                }) {
+                    Ok(path) => {
+                        self.mem.status = Some(format!("Exported to {}", path.display()));
+                        self.mem.report_error = None;
+                    }
+                    Err(err) => self.mem.report_error = Some(err),
+                }
+                Command::none()
+            }
+            Message::DragStarted(id) => {
+                if self.mem.modifiers.control() {
+                    if !self.mem.selected_tasks.remove(&id) {
+                        self.mem.selected_tasks.insert(id);
+                    }
+                    self.mem.selection_anchor = Some(id);
+                } else if self.mem.modifiers.shift() {
+                    let order: Vec<i32> = self
+                        .visible_entries()
+                        .iter()
+                        .map(|entry| entry.id)
+                        .collect();
+                    let anchor = self
+                        .mem
+                        .selection_anchor
+                        .or(self.mem.focused_task)
+                        .and_then(|anchor| order.iter().position(|other| *other == anchor));
+                    let current = order.iter().position(|other| *other == id);
+                    if let (Some(anchor), Some(current)) = (anchor, current) {
+                        let range = anchor.min(current)..=anchor.max(current);
+                        self.mem.selected_tasks = order[range].iter().copied().collect();
+                    }
+                } else {
+                    self.mem.selected_tasks.clear();
+                    self.mem.selection_anchor = Some(id);
+                }
+                self.mem.focused_task = Some(id);
+                self.mem.dragging_task = Some(id);
+                Command::none()
+            }
+            Message::DragDropped(target) => {
+                match self.mem.dragging_task.take() {
+                    Some(id) if id != target => self.reorder_task(id, target),
+                    _ => {}
+                }
+                Command::none()
+            }
+            Message::DragEnded => {
+                // Releasing the mouse outside of any row cancels the drag.
+                self.mem.dragging_task = None;
+                self.mem.dragging_list = None;
+                self.mem.pressed_folder = None;
+                self.mem.calendar_drag = None;
+                self.mem.pressed_day = None;
+                Command::none()
+            }
+            Message::PaletteQueryChanged(input) => {
+                self.mem.palette_query = input;
+                self.mem.palette_selected = 0;
+                Command::none()
+            }
+            Message::PaletteSubmitted => {
+                let chosen =
+                    command_palette::matches(&self.mem.palette_items, &self.mem.palette_query)
+                        .get(self.mem.palette_selected)
+                        .map(|item| item.command.clone());
+                match chosen {
+                    Some(command) => self.update(Message::PaletteCommandChosen(command)),
+                    None => Command::none(),
+                }
+            }
+            Message::PaletteCommandChosen(command) => {
+                if let (State::CommandPalette, Some(ButtonPressed::CommandPalette)) =
+                    (&self.state, &self.button_pressed)
+                {
+                    self.mem.palette_query = String::new();
+                    self.state = State::None;
+                    self.button_pressed = None;
+                }
+                self.run_palette_command(command)
+            }
+            // Moves and resizes are only noted here, `SaveWindowGeometry`
+            // writes them a moment later so a drag is not saved at every step.
+            Message::WindowMoved(x, y) => {
+                if !window_state::is_minimized_position(x, y) {
+                    self.mem.window_moved = Some((x, y));
+                }
+                Command::none()
+            }
+            Message::WindowResized(width, height) => {
+                // The compact focus layout is not the size to come back to.
+                if width > 0 && height > 0 && self.mem.focus.is_none() {
+                    self.mem.window_resized = Some((width, height));
+                }
+                Command::none()
+            }
+            Message::SaveWindowGeometry => {
+                self.save_window_geometry();
+                Command::none()
+            }
+            Message::WindowCloseRequested => {
+                self.save_window_geometry();
+                window::close()
+            }
+        }
+    }
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        let events = subscription::events_with(|event, status| match event {
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key_code,
+                modifiers,
+            }) if status == event::Status::Ignored => {
+                Some(Message::KeyPressed(key_code, modifiers))
+            }
+            Event::Window(window::Event::Moved { x, y }) => Some(Message::WindowMoved(x, y)),
+            Event::Window(window::Event::Resized { width, height }) => {
+                Some(Message::WindowResized(width, height))
+            }
+            Event::Window(window::Event::CloseRequested) => Some(Message::WindowCloseRequested),
+            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                Some(Message::ModifiersChanged(modifiers))
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                Some(Message::DragEnded)
+            }
+            // Clicks focus and unfocus text inputs, Escape unfocuses them.
+            Event::Mouse(mouse::Event::ButtonPressed(_))
+            | Event::Keyboard(keyboard::Event::KeyPressed {
+                key_code: KeyCode::Escape,
+                ..
+            }) => Some(Message::CheckInputFocus),
+            _ => None,
+        });
+
+        let focusing = self
+            .mem
+            .focus
+            .as_ref()
+            .is_some_and(|focus| !focus.is_paused());
+        let mut subscriptions = vec![events];
+        if focusing || self.running_timer().is_some() {
+            subscriptions
+                .push(iced::time::every(std::time::Duration::from_secs(1)).map(|_| Message::Tick));
+        }
+        if self.mem.window_moved.is_some() || self.mem.window_resized.is_some() {
+            subscriptions.push(
+                iced::time::every(std::time::Duration::from_millis(
+                    window_state::SAVE_INTERVAL_MILLIS,
+                ))
+                .map(|_| Message::SaveWindowGeometry),
+            );
+        }
+        let reminders_pending = self
+            .mem
+            .all_tasks
+            .iter()
+            .any(|task| !task.entry.completed && task.entry.remind_at.is_some());
+        if reminders_pending {
+            subscriptions.push(
+                iced::time::every(std::time::Duration::from_secs(
+                    reminders::CHECK_INTERVAL_SECONDS,
+                ))
+                .map(|_| Message::ReminderCheck),
+            );
+        }
+        Subscription::batch(subscriptions)
+    }
+
+    fn view(&self) -> Element<'_, Self::Message> {
+        let statuses: Vec<String> = self
+            .mem
+            .list_meta
+            .statuses()
+            .into_iter()
+            .map(|status| status.name)
+            .collect();
+        let task_entries: Vec<Element<'_, Message>> = self
+            .visible_entries()
+            .into_iter()
+            .map(|entry| {
+                let blockers = self.open_blockers(entry);
+                // Tasks that wait on others are dimmed until those are done.
+                let shade = if blockers.is_empty() {
+                    None
+                } else {
+                    Some(Color {
+                        a: 0.5,
+                        ..self.theme().palette().text
+                    })
+                };
+                let dim = |label: String| match shade {
+                    Some(color) => Text::new(label).style(color),
+                    None => Text::new(label),
+                };
+                let name = dim(format!("Name: {}", &entry.name));
+                let expanded = self.mem.expanded_tasks.contains(&entry.id);
+                let chevron = if expanded {
+                    Icon::ChevronDown
+                } else {
+                    Icon::ChevronRight
+                };
+                let description = row![
+                    dim(format!(
+                        "Description: {}",
+                        markdown::summary(&entry.description)
+                    )),
+                    Button::new(text(chevron.to_string()).font(ICON_FONT).size(12))
+                        .on_press(Message::ToggleTaskDetails(entry.id))
+                        .style(ui_theme::button_theme())
+                        .padding([2, 4]),
+                ]
+                .align_items(Alignment::Center)
+                .spacing(5);
+                let tags = dim(format!("Tags: {}", &entry.tags.join(", ")));
+                let mut text_column = Column::new()
+                    .spacing(10)
+                    .push(name)
+                    .push(description)
+                    .width(Length::Fill);
+                if expanded && !entry.description.is_empty() {
+                    text_column = text_column.push(
+                        container(markdown::view(
+                            &markdown::parse(&entry.description),
+                            self.theme().palette().primary,
+                            self.settings.font_size,
+                        ))
+                        .padding([0, 0, 0, 10]),
+                    );
+                }
+                text_column = text_column.push(tags);
+                let mut details = Vec::new();
+                if entry.priority != Priority::None {
+                    details.push(format!("Priority: {}", entry.priority));
+                }
+                if let Some(due_date) = entry.due_date {
+                    details.push(format!(
+                        "Due: {}",
+                        due_date.format(self.settings.date_format.pattern())
+                    ));
+                }
+                if let Some(estimate) = entry.estimate {
+                    details.push(format!("Estimate: {}", estimate));
+                }
+                if let Some(remind_at) = entry.remind_at {
+                    details.push(format!(
+                        "Reminder: {}",
+                        remind_at.format(&self.reminder_pattern())
+                    ));
+                }
+                let tracked = time_tracking::tracked(&entry.time_sessions, Local::now());
+                if !tracked.is_zero() {
+                    details.push(format!(
+                        "Tracked: {}",
+                        time_tracking::format_duration(tracked)
+                    ));
+                }
+                if !entry.pomodoros.is_empty() {
+                    details.push(format!("Pomodoros: {}", entry.pomodoros.len()));
+                }
+                if !details.is_empty() {
+                    text_column = text_column.push(dim(details.join("   ")));
+                }
+                if !blockers.is_empty() {
+                    text_column = text_column.push(
+                        row![
+                            text(Icon::Lock.to_string()).font(ICON_FONT),
+                            text(format!("Blocked by {}", blockers.join(", "))),
+                        ]
+                        .spacing(5),
+                    );
+                }
+
+                let delete_button = Button::new(
+                    Text::new("Delete").horizontal_alignment(alignment::Horizontal::Center),
+                )
+                .width(60)
+                .on_press(Message::ButtonDeletePressed(entry.id))
+                .style(ui_theme::button_theme());
+
+                let edit_button = Button::new(
+                    Text::new("  Edit  ").horizontal_alignment(alignment::Horizontal::Center),
+                )
+                .width(60)
+                .on_press(Message::ButtonEditPressed(entry.id))
+                .style(ui_theme::button_theme());
+
+                let completed_box = Checkbox::new("Completed", entry.completed, move |checked| {
+                    Message::CheckboxChanged(entry.id, checked)
+                })
+                .style(ui_theme::checkbox_theme());
+
+                let status_badge = pick_list(
+                    statuses.clone(),
+                    Some(entry.status.clone()),
+                    move |status| Message::StatusSelected(entry.id, status),
+                )
+                .text_size(12)
+                .padding([2, 6])
+                .style(ui_theme::pick_list_theme())
+                .width(100);
+
+                let move_button = Button::new(
+                    Text::new("Move to…").horizontal_alignment(alignment::Horizontal::Center),
+                )
+                .width(80)
+                .on_press(Message::ButtonMovePressed(entry.id))
+                .style(ui_theme::button_theme());
+
+                let copy_button = Button::new(
+                    Text::new("Copy to…").horizontal_alignment(alignment::Horizontal::Center),
+                )
+                .width(80)
+                .on_press(Message::ButtonCopyPressed(entry.id))
+                .style(ui_theme::button_theme());
+
+                let timer_label = if time_tracking::is_running(&entry.time_sessions) {
+                    "Stop"
+                } else {
+                    "Start"
+                };
+                let timer_button = Button::new(
+                    Text::new(timer_label).horizontal_alignment(alignment::Horizontal::Center),
+                )
+                .width(60)
+                .on_press(Message::TimerToggled(self.mem.list_name.clone(), entry.id))
+                .style(ui_theme::button_theme());
+
+                let button_column = Column::new()
+                    .align_items(Alignment::Center)
+                    .push(edit_button)
+                    .push(delete_button)
+                    .push(timer_button)
+                    .spacing(5);
+
+                let history_button = Button::new(
+                    Text::new("History").horizontal_alignment(alignment::Horizontal::Center),
+                )
+                .width(80)
+                .on_press(Message::ButtonHistoryPressed(entry.id))
+                .style(ui_theme::button_theme());
+
+                let focus_button = Button::new(
+                    Text::new("Focus").horizontal_alignment(alignment::Horizontal::Center),
+                )
+                .width(80)
+                .on_press(Message::FocusStarted(self.mem.list_name.clone(), entry.id))
+                .style(ui_theme::button_theme());
+
+                // Moving, copying, history and focus sessions are used less
+                // often, they wait in the unfolded details to keep the row
+                // narrow.
+                if expanded {
+                    text_column = text_column.push(
+                        row![move_button, copy_button, history_button, focus_button].spacing(5),
+                    );
+                }
+
+                let text_column = tooltip(
+                    text_column,
+                    self.timestamps(entry),
+                    tooltip::Position::FollowCursor,
+                )
+                .style(ui_theme::container_theme());
+
+                let task_container = Container::new(
+                    Row::new()
+                        .align_items(Alignment::Center)
+                        .spacing(10)
+                        .push(text_column)
+                        .push(
+                            Column::new().push(
+                                Row::new()
+                                    .push(status_badge)
+                                    .push(completed_box)
+                                    .align_items(Alignment::Center)
+                                    .push(button_column)
+                                    .spacing(5),
+                            ),
+                        ),
+                )
+                .style(if self.mem.selected_tasks.contains(&entry.id) {
+                    ui_theme::selected_container_theme()
+                } else if self.mem.focused_task == Some(entry.id) {
+                    ui_theme::focused_container_theme()
+                } else {
+                    ui_theme::container_theme()
+                })
+                .width(Length::Fill)
+                .padding(5);
+
+                mouse_area(task_container)
+                    .on_press(Message::DragStarted(entry.id))
+                    .on_release(Message::DragDropped(entry.id))
+                    .into()
+            })
+            .collect();
+
+        let mut task_container = Column::new().spacing(10);
+        for task_entry in task_entries {
+            task_container = task_container.push(task_entry);
+        }
+
+        let task_scrollbar = Scrollable::new(task_container)
+            .id(task_scrollable_id())
+            .style(ui_theme::scrollable_theme())
+            .width(Length::Fill)
+            .height(Length::Fill);
+
+        let underlay = match self.state {
+            State::None if self.mem.focus.is_some() => self.focus_content(),
+            State::None => {
+                let pick_list = pick_list(
+                    self.mem.lists.clone(),
+                    list_info(&self.mem.lists, self.mem.selected_file.as_deref()),
+                    |list| Message::FileSelected(list.file),
+                )
+                .width(150);
+
+                let button = |label, message| {
+                    button(text(label).horizontal_alignment(alignment::Horizontal::Center))
+                        .style(ui_theme::button_theme())
+                        .width(90)
+                        .on_press(message)
+                };
+
+                let search_bar = TextInput::new("Search", self.mem.search_term.as_str())
+                    .id(search_input_id())
+                    .on_input(Message::SearchChanged)
+                    .on_submit(Message::SearchButtonPressed);
+                let search_button = button("Search", Message::SearchButtonPressed);
+
+                let mut footer = row![
+                    button("New Task", Message::ButtonCreatePressed),
+                    search_bar.style(ui_theme::text_input_theme()).width(150),
+                    search_button,
+                    button("Select All", Message::SelectAll),
+                ]
+                .align_items(Alignment::Center)
+                .spacing(5)
+                .padding(2);
+                if !self.mem.selected_tasks.is_empty() {
+                    footer = footer
+                        .push(button("Edit Selected", Message::BulkPressed))
+                        .push(button("Clear", Message::ClearSelection));
+                }
+                footer = footer.push(text(self.mem.status.as_deref().unwrap_or_default()).size(12));
+
+                let sidebar_button = Button::new(
+                    text(Icon::LayoutSidebar.to_string())
+                        .font(ICON_FONT)
+                        .horizontal_alignment(alignment::Horizontal::Center),
+                )
+                .style(ui_theme::button_theme())
+                .on_press(Message::ToggleSidebar);
+
+                let mut header = row![
+                    sidebar_button,
+                    button("New List", Message::CreateNewFileButton)
+                ]
+                .align_items(Alignment::Center)
+                .spacing(5)
+                .padding(2);
+                // The sidebar takes over switching lists while it is shown.
+                if !self.sidebar_visible() {
+                    header = header.push(pick_list.style(ui_theme::pick_list_theme()));
+                }
+                header = header
+                    .push(button("Delete List", Message::DeleteListPressed))
+                    .push(button("Manage", Message::ManageListPressed))
+                    .push(button("Time Report", Message::TimeReportPressed))
+                    .push(button("Settings", Message::SettingsButtonPressed));
+
+                let content: Element<'_, Message> = match self.mem.smart_view {
+                    Some(view) => self.smart_view_content(view).into(),
+                    None if self.mem.calendar.is_some() => self.calendar_content().into(),
+                    None if self.mem.agenda => self.agenda_content().into(),
+                    None if self.mem.list_meta.view == ListView::Board => {
+                        column![self.list_header(), self.board()].spacing(10).into()
+                    }
+                    None => column![
+                        self.list_header(),
+                        task_scrollbar, // Ensure the task scrollbar is added after the other elements
+                    ]
+                    .spacing(10)
+                    .into(),
+                };
+                let mut body = Row::new().spacing(10).height(Length::Fill);
+                if self.sidebar_visible() {
+                    body = body.push(self.sidebar());
+                }
+                body = body.push(content);
+
+                let mut page = column![header];
+                if !self.mem.reminders.is_empty() {
+                    page = page.push(self.reminder_bar());
+                }
+
+                container(
+                    page.push(body)
+                        .push(footer)
+                        .align_items(Alignment::Center)
+                        .spacing(10)
+                        .padding(10),
+                )
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .center_x()
+            }
+            _ => container(task_scrollbar)
+                .padding(10)
+                .width(Length::Fill)
+                .height(Length::Fill),
+        };
+
+        let overlay = self.button_pressed.as_ref().map(|button_pressed| {
+            let head_string = match button_pressed {
+                ButtonPressed::Create => "New Task",
+                ButtonPressed::Edit => "Edit Task",
+                ButtonPressed::NewFile => "New List",
+                ButtonPressed::DeleteList => "Delete List",
+                ButtonPressed::DeleteTask => "Delete Task",
+                ButtonPressed::Settings => "Settings",
+                ButtonPressed::CommandPalette => "Command Palette",
+                ButtonPressed::Bulk => "Selected Tasks",
+                ButtonPressed::ManageList => "Manage List",
+                ButtonPressed::ListDetails => "List Details",
+                ButtonPressed::History => "Task History",
+                ButtonPressed::TimeReport => "Time Report",
+                ButtonPressed::Transfer => {
+                    if self.mem.transfer_keep_source {
+                        "Copy To List"
+                    } else {
+                        "Move To List"
+                    }
+                }
+            };
+
+            let body_string = match button_pressed {
+                ButtonPressed::Create => {
+                    let task_name_input = text_input("Name", &self.mem.task_name)
+                        .id(task_name_input_id())
+                        .on_input(Message::TaskNameChanged)
+                        .on_submit(Message::TaskSubmitted)
+                        .style(ui_theme::text_input_theme())
+                        .width(Length::Fill);
+
+                    let task_description_input = self.description_editor();
+
+                    let tags_input = text_input("Tags", &self.mem.task_tags.join(","))
+                        .on_input(Message::TagsChanged)
+                        .on_submit(Message::TaskSubmitted)
+                        .style(ui_theme::text_input_theme())
+                        .width(Length::Fill);
+
+                    let due_input = text_input(
+                        &format!("Due date ({})", self.settings.date_format),
+                        &self.mem.task_due,
+                    )
+                    .on_input(Message::TaskDueChanged)
+                    .on_submit(Message::TaskSubmitted)
+                    .style(ui_theme::text_input_theme())
+                    .width(Length::Fill);
+
+                    let estimate_input =
+                        text_input("Estimate, e.g. 30m, 2h or 3pt", &self.mem.task_estimate)
+                            .on_input(Message::TaskEstimateChanged)
+                            .on_submit(Message::TaskSubmitted)
+                            .style(ui_theme::text_input_theme())
+                            .width(Length::Fill);
+
+                    let reminder_input = text_input(
+                        &format!("Reminder ({} HH:MM)", self.settings.date_format),
+                        &self.mem.task_reminder,
+                    )
+                    .on_input(Message::TaskReminderChanged)
+                    .on_submit(Message::TaskSubmitted)
+                    .style(ui_theme::text_input_theme())
+                    .width(Length::Fill);
+
+                    let ok_button =
+                        button(text("Ok").horizontal_alignment(alignment::Horizontal::Center))
+                            .on_press(Message::TaskSubmitted)
+                            .style(ui_theme::button_theme())
+                            .width(Length::Fill);
+
+                    let cancel_button =
+                        button(text("Cancel").horizontal_alignment(alignment::Horizontal::Center))
+                            .on_press(Message::CloseOverlay)
+                            .style(ui_theme::button_theme())
+                            .width(Length::Fill);
+
+                    let mut body = column![
+                        task_name_input,
+                        task_description_input,
+                        tags_input,
+                        due_input,
+                        estimate_input,
+                        reminder_input
+                    ]
+                    .spacing(10);
+                    if let Some(error) = &self.mem.task_error {
+                        body = body.push(text(error));
+                    }
+                    body.push(row![ok_button, cancel_button].spacing(10))
+                }
+                ButtonPressed::Edit => {
+                    let task_name_input = text_input("Name", &self.mem.task_name)
+                        .id(task_name_input_id())
+                        .on_input(Message::TaskNameEdited)
+                        .on_submit(Message::TaskEdited(self.mem.editing_task_id.unwrap()))
+                        .style(ui_theme::text_input_theme())
+                        .width(Length::Fill);
+
+                    let task_description_input = self.description_editor();
+
+                    let tags_input = text_input("Tags", &self.mem.task_tags.join(","))
+                        .on_input(Message::TagsChanged)
+                        .on_submit(Message::TaskEdited(self.mem.editing_task_id.unwrap()))
+                        .style(ui_theme::text_input_theme())
+                        .width(Length::Fill);
+
+                    let due_input = text_input(
+                        &format!("Due date ({})", self.settings.date_format),
+                        &self.mem.task_due,
+                    )
+                    .on_input(Message::TaskDueChanged)
+                    .on_submit(Message::TaskEdited(self.mem.editing_task_id.unwrap()))
+                    .style(ui_theme::text_input_theme())
+                    .width(Length::Fill);
+
+                    let estimate_input =
+                        text_input("Estimate, e.g. 30m, 2h or 3pt", &self.mem.task_estimate)
+                            .on_input(Message::TaskEstimateChanged)
+                            .on_submit(Message::TaskEdited(self.mem.editing_task_id.unwrap()))
+                            .style(ui_theme::text_input_theme())
+                            .width(Length::Fill);
+
+                    let reminder_input = text_input(
+                        &format!("Reminder ({} HH:MM)", self.settings.date_format),
+                        &self.mem.task_reminder,
+                    )
+                    .on_input(Message::TaskReminderChanged)
+                    .on_submit(Message::TaskEdited(self.mem.editing_task_id.unwrap()))
+                    .style(ui_theme::text_input_theme())
+                    .width(Length::Fill);
+
+                    let ok_button =
+                        button(text("Ok").horizontal_alignment(alignment::Horizontal::Center))
+                            .on_press(Message::TaskEdited(self.mem.editing_task_id.unwrap()))
+                            .style(ui_theme::button_theme())
+                            .width(Length::Fill);
+
+                    let cancel_button =
+                        button(text("Cancel").horizontal_alignment(alignment::Horizontal::Center))
+                            .on_press(Message::CloseOverlay)
+                            .style(ui_theme::button_theme())
+                            .width(Length::Fill);
+
+                    let mut body = column![
+                        task_name_input,
+                        task_description_input,
+                        tags_input,
+                        due_input,
+                        estimate_input,
+                        reminder_input
+                    ]
+                    .spacing(10);
+                    for blocker in &self.mem.task_blockers {
+                        body = body.push(
+                            row![
+                                text(Icon::Lock.to_string()).font(ICON_FONT),
+                                text(format!("Blocked by {}", self.task_label(blocker)))
+                                    .width(Length::Fill),
+                                button(text("Remove"))
+                                    .on_press(Message::BlockerRemoved(blocker.clone()))
+                                    .style(ui_theme::button_theme()),
+                            ]
+                            .align_items(Alignment::Center)
+                            .spacing(10),
+                        );
+                    }
+                    body = body.push(
+                        pick_list(self.blocker_choices(), None, Message::BlockerSelected)
+                            .placeholder("Add a task that blocks this one")
+                            .style(ui_theme::pick_list_theme())
+                            .width(Length::Fill),
+                    );
+                    if let Some(task) = self
+                        .mem
+                        .task_entries
+                        .iter()
+                        .find(|entry| Some(entry.id) == self.mem.editing_task_id)
+                    {
+                        body = body.push(text(self.timestamps(task)).size(12));
+                    }
+                    if let Some(error) = &self.mem.task_error {
+                        body = body.push(text(error));
+                    }
+                    body.push(row![ok_button, cancel_button].spacing(10))
+                }
+                ButtonPressed::NewFile => {
+                    let list_name_input = text_input("List Name", &self.mem.file_name)
+                        .on_input(Message::NewFileNameChanged)
+                        .on_submit(Message::CreateNewFile)
+                        .style(ui_theme::text_input_theme())
+                        .width(Length::Fill);
+
+                    let ok_button =
+                        button(text("Ok").horizontal_alignment(alignment::Horizontal::Center))
+                            .on_press(Message::CreateNewFile)
+                            .style(ui_theme::button_theme())
+                            .width(Length::Fill);
+
+                    let cancel_button =
+                        button(text("Cancel").horizontal_alignment(alignment::Horizontal::Center))
+                            .on_press(Message::CloseOverlay)
+                            .style(ui_theme::button_theme())
+                            .width(Length::Fill);
+
+                    let button_row = Row::new()
+                        .push(ok_button)
+                        .push(cancel_button)
+                        .align_items(Alignment::Center)
+                        .spacing(10);
+
+                    let mut body = column![list_name_input].spacing(10);
+                    if let Some(error) = &self.mem.new_list_error {
+                        body = body.push(text(error));
+                    }
+                    body.push(button_row)
+                }
+
+                ButtonPressed::DeleteList => {
+                    let ok_button =
+                        button(text("Ok").horizontal_alignment(alignment::Horizontal::Center))
+                            .on_press(Message::DeleteList)
+                            .style(ui_theme::button_theme())
+                            .width(Length::Fill);
+
+                    let cancel_button =
+                        button(text("Cancel").horizontal_alignment(alignment::Horizontal::Center))
+                            .on_press(Message::CloseOverlay)
+                            .style(ui_theme::button_theme())
+                            .width(Length::Fill);
+
+                    let button_row = Row::new()
+                        .push(ok_button)
+                        .push(cancel_button)
+                        .align_items(Alignment::Center)
+                        .spacing(10);
+
+                    let mut body = column![].spacing(10);
+                    if let Some(error) = &self.mem.delete_list_error {
+                        body = body.push(text(error));
+                    }
+                    body.push(button_row)
+                }
+
+                ButtonPressed::DeleteTask => {
+                    let ok_button =
+                        button(text("Ok").horizontal_alignment(alignment::Horizontal::Center))
+                            .on_press(Message::DeleteTask)
+                            .style(ui_theme::button_theme())
+                            .width(Length::Fill);
+
+                    let cancel_button =
+                        button(text("Cancel").horizontal_alignment(alignment::Horizontal::Center))
+                            .on_press(Message::CloseOverlay)
+                            .style(ui_theme::button_theme())
+                            .width(Length::Fill);
+
+                    let button_row = Row::new()
+                        .push(ok_button)
+                        .push(cancel_button)
+                        .align_items(Alignment::Center)
+                        .spacing(10);
+
+                    column![button_row]
+                }
+
+                ButtonPressed::Settings => self.settings_card(),
+                ButtonPressed::CommandPalette => self.command_palette_card(),
+                ButtonPressed::Bulk => self.bulk_card(),
+                ButtonPressed::ManageList => self.manage_list_card(),
+                ButtonPressed::ListDetails => self.list_details_card(),
+                ButtonPressed::History => self.history_card(),
+                ButtonPressed::TimeReport => self.time_report_card(),
+                ButtonPressed::Transfer => {
+                    let other_lists: Vec<ListInfo> = self
+                        .mem
+                        .lists
+                        .iter()
+                        .filter(|list| list.file != self.mem.list_name)
+                        .cloned()
+                        .collect();
+                    let target_pick = pick_list(
+                        other_lists,
+                        list_info(&self.mem.lists, self.mem.transfer_target.as_deref()),
+                        |list| Message::TransferTargetSelected(list.file),
+                    )
+                    .placeholder("Destination list")
+                    .style(ui_theme::pick_list_theme())
+                    .width(Length::Fill);
+
+                    let ok_button =
+                        button(text("Ok").horizontal_alignment(alignment::Horizontal::Center))
+                            .on_press(Message::TransferTask)
+                            .style(ui_theme::button_theme())
+                            .width(Length::Fill);
+
+                    let cancel_button =
+                        button(text("Cancel").horizontal_alignment(alignment::Horizontal::Center))
+                            .on_press(Message::CloseOverlay)
+                            .style(ui_theme::button_theme())
+                            .width(Length::Fill);
+
+                    let button_row = Row::new()
+                        .push(ok_button)
+                        .push(cancel_button)
+                        .align_items(Alignment::Center)
+                        .spacing(10);
+
+                    let mut body = column![target_pick].spacing(10);
+                    if let Some(error) = &self.mem.transfer_error {
+                        body = body.push(text(error));
+                    }
+                    body.push(button_row)
+                }
+            };
+
+            card(text(head_string), body_string)
+                .width(Length::from(500))
+                .style(ui_theme::card_theme())
+        });
+
+        modal(underlay, overlay)
+            .backdrop(Message::CloseOverlay)
+            .on_esc(Message::CloseOverlay)
+            .into()
+    }
+
+    fn theme(&self) -> Self::Theme {
+        self.themes
+            .iter()
+            .find(|theme| theme.name == self.settings.theme)
+            .map(ThemeDefinition::theme)
+            .unwrap_or(Theme::Dark)
+    }
+}
+
+impl TaskList {
+    fn run_action(&mut self, action: Action) -> Command<Message> {
+        let focused = self
+            .mem
+            .focused_task
+            .and_then(|id| self.mem.task_entries.iter().find(|entry| entry.id == id))
+            .cloned();
+
+        match action {
+            Action::NewTask => self.update(Message::ButtonCreatePressed),
+            Action::EditTask => match focused {
+                Some(task) => self.update(Message::ButtonEditPressed(task.id)),
+                None => Command::none(),
+            },
+            Action::ToggleComplete => match focused {
+                Some(task) => self.update(Message::CheckboxChanged(task.id, !task.completed)),
+                None => Command::none(),
+            },
+            Action::DeleteTask => match focused {
+                Some(task) => self.update(Message::ButtonDeletePressed(task.id)),
+                None => Command::none(),
+            },
+            Action::FocusSearch => {
+                self.mem.input_focused = true;
+                text_input::focus(search_input_id())
+            }
+            Action::SwitchList => {
+                let lists = &self.mem.lists;
+                let next = lists
+                    .iter()
+                    .position(|list| list.file == self.mem.list_name)
+                    .map(|index| (index + 1) % lists.len())
+                    .unwrap_or(0);
+                match lists.get(next).map(|list| list.file.clone()) {
+                    Some(file) => self.update(Message::FileSelected(file)),
+                    None => Command::none(),
+                }
+            }
+            Action::NextTask => self.move_focus(1),
+            Action::PreviousTask => self.move_focus(-1),
+            Action::MoveTaskUp => self.move_focused_task(-1),
+            Action::MoveTaskDown => self.move_focused_task(1),
+            Action::CommandPalette => self.open_command_palette(),
+            Action::SelectAll => self.update(Message::SelectAll),
+        }
+    }
+
+    /// Passes on what a write returned. A failed write is shown in the
+    /// footer and the open list is read again, so it does not keep showing
+    /// the change as saved.
+    fn saved<T>(&mut self, result: Result<T, String>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(err) => {
+                match json_handling::read_tasks(self.mem.list_name.clone()) {
+                    Ok(tasks) => {
+                        self.mem.task_entries = tasks.tasks;
+                        self.mem.list_meta = tasks.meta;
+                    }
+                    Err(_) => {
+                        self.mem.task_entries.clear();
+                        self.mem.list_meta = ListMeta::default();
+                    }
+                }
+                self.mem.status = Some(err);
+                None
+            }
+        }
+    }
+
+    /// Applies a change to every selected task, stores the list and closes the
+    /// bulk card.
+    fn update_selected(&mut self, change: impl Fn(&mut TaskEntry)) {
+        for task in self
+            .mem
+            .task_entries
+            .iter_mut()
+            .filter(|entry| self.mem.selected_tasks.contains(&entry.id))
+        {
+            change(task);
+        }
+        self.saved(json_handling::write_task(
+            &Tasks {
+                tasks: self.mem.task_entries.clone(),
+                meta: ListMeta::default(),
+            },
+            self.mem.list_name.clone(),
+        ));
+        self.refresh_list(&self.mem.list_name.clone());
+        self.close_bulk_card();
+    }
+
+    /// Reloads the list after tasks were removed from it or changed. The
+    /// selected tasks may be gone, so the selection is cleared.
+    fn reload_after_bulk(&mut self, changed: &[String]) {
+        self.mem.task_entries = json_handling::read_tasks(self.mem.list_name.clone())
+            .unwrap_or_default()
+            .tasks;
+        self.mem.selected_tasks.clear();
+        self.mem.selection_anchor = None;
+        self.mem.focused_task = None;
+        self.refresh_changed(changed);
+        self.close_bulk_card();
+    }
+
+    fn close_bulk_card(&mut self) {
+        match (&self.state, &self.button_pressed) {
+            (State::Bulk, Some(ButtonPressed::Bulk)) => {
+                self.mem.bulk_error = None;
+                self.state = State::None;
+                self.button_pressed = None;
+            }
+            _ => println!("Attempted to close overlay while in another mode."),
+        }
+    }
+
+    fn close_manage_list_card(&mut self) {
+        match (&self.state, &self.button_pressed) {
+            (State::ManageList, Some(ButtonPressed::ManageList)) => {
+                self.mem.list_error = None;
+                self.state = State::None;
+                self.button_pressed = None;
+            }
+            _ => println!("Attempted to close overlay while in another mode."),
+        }
+    }
+
+    /// The list to show once `leaving` is gone: the default list, or the
+    /// first remaining one when `leaving` is the default list.
+    fn fallback_list(&self, leaving: &str) -> String {
+        if self.settings.default_list != leaving {
+            return self.settings.default_list.clone();
+        }
+        self.mem
+            .lists
+            .iter()
+            .map(|list| list.file.clone())
+            .find(|file| file != leaving)
+            .unwrap_or_else(|| self.settings.default_list.clone())
+    }
+
+    /// Writes the window geometry with the moves and resizes reported since
+    /// the last save. A maximized window keeps the size to restore to.
+    fn save_window_geometry(&mut self) {
+        let moved = self.mem.window_moved.take();
+        let resized = self.mem.window_resized.take();
+        let geometry = &mut self.mem.window_geometry;
+        geometry.maximized = window_state::is_maximized();
+        if !geometry.maximized {
+            if let Some((x, y)) = moved {
+                geometry.x = x;
+                geometry.y = y;
+            }
+            if let Some((width, height)) = resized {
+                geometry.width = width;
+                geometry.height = height;
+            }
+        }
+        json_handling::write_window_geometry(geometry);
+    }
+
+    /// Rereads every list. Used when switching lists and views, after a
+    /// change only the lists that were written are reread.
+    fn refresh_lists(&mut self) {
+        let lists = json_handling::read_all_lists();
+        self.mem.all_tasks = lists
+            .iter()
+            .flat_map(|(list, tasks)| {
+                tasks.tasks.iter().map(|entry| ListedTask {
+                    list: list.file.clone(),
+                    list_name: list.name.clone(),
+                    entry: entry.clone(),
+                })
+            })
+            .collect();
+        self.mem.lists = lists.into_iter().map(|(list, _)| list).collect();
+        self.sync_shown_entries();
+    }
+
+    /// Rereads one list after it was written, keeping the tasks of all lists
+    /// in picker order.
+    fn refresh_list(&mut self, file: &str) {
+        let tasks = match json_handling::read_tasks(file.to_string()) {
+            Ok(tasks) => tasks,
+            Err(err) => {
+                println!("{}", err);
+                return;
+            }
+        };
+        let info = ListInfo::new(file.to_string(), Some(&tasks));
+        let listed: Vec<ListedTask> = tasks
+            .tasks
+            .into_iter()
+            .map(|entry| ListedTask {
+                list: file.to_string(),
+                list_name: info.name.clone(),
+                entry,
+            })
+            .collect();
+
+        self.mem.lists.retain(|list| list.file != file);
+        self.mem.lists.push(info);
+        json_handling::sort_lists(&mut self.mem.lists);
+
+        self.mem.all_tasks.retain(|task| task.list != file);
+        let rank = |list: &str| self.mem.lists.iter().position(|info| info.file == list);
+        let at = self
+            .mem
+            .all_tasks
+            .iter()
+            .position(|task| rank(&task.list) > rank(file))
+            .unwrap_or(self.mem.all_tasks.len());
+        self.mem.all_tasks.splice(at..at, listed);
+        self.sync_shown_entries();
+    }
+
+    fn refresh_changed(&mut self, files: &[String]) {
+        for file in files {
+            self.refresh_list(file);
+        }
+    }
+
+    /// Timestamps are set while writing, the shown tasks pick them up here.
+    fn sync_shown_entries(&mut self) {
+        for entry in self.mem.task_entries.iter_mut() {
+            if let Some(stored) = self
+                .mem
+                .all_tasks
+                .iter()
+                .find(|task| task.list == self.mem.list_name && task.entry.id == entry.id)
+            {
+                entry.created_at = stored.entry.created_at;
+                entry.updated_at = stored.entry.updated_at;
+                entry.completed_at = stored.entry.completed_at;
+                entry.time_sessions = stored.entry.time_sessions.clone();
+                entry.pomodoros = stored.entry.pomodoros.clone();
+                entry.remind_at = stored.entry.remind_at;
+            }
+        }
+    }
+
+    /// When the task was created, last changed and completed, as far as
+    /// known.
+    fn timestamps(&self, entry: &TaskEntry) -> String {
+        let pattern = format!("{} %H:%M", self.settings.date_format.pattern());
+        let mut lines = Vec::new();
+        for (label, time) in [
+            ("Created", entry.created_at),
+            ("Updated", entry.updated_at),
+            ("Completed", entry.completed_at),
+        ] {
+            if let Some(time) = time {
+                lines.push(format!("{} {}", label, time.format(&pattern)));
+            }
+        }
+        if lines.is_empty() {
+            "No timestamps recorded yet".to_string()
+        } else {
+            lines.join("\n")
+        }
+    }
+
+    fn sidebar_visible(&self) -> bool {
+        !self.settings.sidebar_collapsed
+            && (self.mem.window_geometry.maximized
+                || self.mem.window_geometry.width >= SIDEBAR_MIN_WINDOW_WIDTH)
+    }
+
+    /// Smart views and lists with their task counts.
+    fn sidebar(&self) -> Element<'_, Message> {
+        let today = today();
+        let entry = |content: Row<'static, Message>, message: Message, active: bool| {
+            button(content.align_items(Alignment::Center).spacing(6))
+                .on_press(message)
+                .width(Length::Fill)
+                .style(if active {
+                    ui_theme::button_theme()
+                } else {
+                    iced::theme::Button::Text
+                })
+        };
+
+        let mut items = Column::new().spacing(2).push(text("Views").size(13));
+        for view in SmartView::ALL {
+            let count = self
+                .mem
+                .all_tasks
+                .iter()
+                .filter(|task| view.contains(&task.entry, today))
+                .count();
+            items = items.push(entry(
+                row![
+                    text(view.to_string()).width(Length::Fill),
+                    text(count.to_string()).size(13),
+                ],
+                Message::SmartViewSelected(view),
+                self.mem.smart_view == Some(view),
+            ));
+        }
+
+        let agenda_count = self
+            .mem
+            .all_tasks
+            .iter()
+            .filter(|task| {
+                AgendaSection::of(&task.entry, today, self.settings.week_start)
+                    .is_some_and(|section| section <= AgendaSection::Today)
+            })
+            .count();
+        items = items.push(entry(
+            row![
+                text(Icon::ListTask.to_string()).font(ICON_FONT),
+                text("Agenda").width(Length::Fill),
+                text(agenda_count.to_string()).size(13),
+            ],
+            Message::AgendaOpened,
+            self.mem.agenda,
+        ));
+        items = items.push(entry(
+            row![
+                text(Icon::Calendar.to_string()).font(ICON_FONT),
+                text("Calendar").width(Length::Fill),
+            ],
+            Message::CalendarOpened,
+            self.mem.calendar.is_some(),
+        ));
+
+        let lists_header = mouse_area(container(text("Lists").size(13)).width(Length::Fill))
+            .on_release(Message::FolderReleased(String::new()));
+        items = items.push(lists_header);
+        items = self.push_folder(items, "", 0);
+
+        Scrollable::new(items.padding([0, 10, 0, 0]))
+            .style(ui_theme::scrollable_theme())
+            .width(220)
+            .height(Length::Fill)
+            .into()
+    }
+
+    /// Adds the sub folders and lists of `folder` to the sidebar, sub folders
+    /// first, indented by their depth.
+    fn push_folder<'a>(
+        &'a self,
+        mut items: Column<'a, Message>,
+        folder: &str,
+        depth: u16,
+    ) -> Column<'a, Message> {
+        let palette = self.theme().palette();
+        let indent = f32::from(depth) * 14.0;
+
+        let mut children: BTreeSet<String> = BTreeSet::new();
+        for list in &self.mem.lists {
+            let rest = if folder.is_empty() {
+                Some(list.folder.as_str()).filter(|rest| !rest.is_empty())
+            } else {
+                list.folder
+                    .strip_prefix(folder)
+                    .and_then(|rest| rest.strip_prefix('/'))
+            };
+            if let Some(child) = rest.and_then(|rest| rest.split('/').next()) {
+                children.insert(child.to_string());
+            }
+        }
+
+        for child in children {
+            let path = if folder.is_empty() {
+                child.clone()
+            } else {
+                format!("{}/{}", folder, child)
+            };
+            let collapsed = self.settings.collapsed_folders.contains(&path);
+            let (open, total) = self
+                .mem
+                .lists
+                .iter()
+                .filter(|list| {
+                    list.folder == path || list.folder.starts_with(&format!("{}/", path))
+                })
+                .fold((0, 0), |(open, total), list| {
+                    (open + list.open, total + list.total)
+                });
+
+            let chevron = if collapsed {
+                Icon::ChevronRight
+            } else {
+                Icon::ChevronDown
+            };
+            let header = container(
+                row![
+                    text(chevron.to_string()).font(ICON_FONT).size(12),
+                    text(Icon::Folder.to_string()).font(ICON_FONT),
+                    text(&child).width(Length::Fill),
+                    text(format!("{}/{}", open, total)).size(13),
+                ]
+                .align_items(Alignment::Center)
+                .spacing(6),
+            )
+            .width(Length::Fill)
+            .padding([5, 5, 5, (indent + 5.0) as u16]);
+            items = items.push(
+                mouse_area(header)
+                    .on_press(Message::FolderPressed(path.clone()))
+                    .on_release(Message::FolderReleased(path.clone())),
+            );
+
+            if !collapsed {
+                items = self.push_folder(items, &path, depth + 1);
+            }
+        }
+
+        for list in self.mem.lists.iter().filter(|list| list.folder == folder) {
+            let mut content = row![
+                text(list.icon.icon().to_string())
+                    .font(ICON_FONT)
+                    .style(list.color.color().unwrap_or(palette.primary)),
+                text(&list.name).width(Length::Fill),
+                text(format!("{}/{}", list.open, list.total)).size(13),
+            ]
+            .align_items(Alignment::Center)
+            .spacing(6);
+            if list.overdue > 0 {
+                content = content.push(
+                    text(format!("{} late", list.overdue))
+                        .size(13)
+                        .style(palette.danger),
+                );
+            }
+
+            let active = self.showing_list() && list.file == self.mem.list_name;
+            let entry = container(content)
+                .width(Length::Fill)
+                .padding([5, 5, 5, (indent + 5.0) as u16])
+                .style(if active {
+                    ui_theme::focused_container_theme()
+                } else {
+                    iced::theme::Container::Transparent
+                });
+            items = items.push(
+                mouse_area(entry)
+                    .on_press(Message::ListPressed(list.file.clone()))
+                    .on_release(Message::ListReleased(list.file.clone())),
+            );
+        }
+
+        items
+    }
+
+    fn move_list(&mut self, file: &str, folder: &str) {
+        if let Err(err) = json_handling::move_list_to_folder(file, folder) {
+            self.mem.status = Some(err);
+            return;
+        }
+        if file == self.mem.list_name {
+            self.mem.list_meta.folder = json_handling::normalize_folder(folder);
+        }
+        self.refresh_list(file);
+    }
+
+    /// Tasks from every list that belong in a smart view.
+    fn smart_view_content(&self, view: SmartView) -> Column<'_, Message> {
+        let today = today();
+        let mut tasks: Vec<&ListedTask> = self
+            .mem
+            .all_tasks
+            .iter()
+            .filter(|task| view.contains(&task.entry, today))
+            .collect();
+        tasks.sort_by_key(|task| {
+            (
+                task.entry.due_date.is_none(),
+                task.entry.due_date,
+                task.entry.name.to_lowercase(),
+            )
+        });
+
+        let rows = tasks
+            .into_iter()
+            .fold(Column::new().spacing(10), |column, task| {
+                column.push(self.listed_task_row(task, today))
+            });
+
+        column![
+            text(view.to_string()).size(20),
+            Scrollable::new(rows)
+                .style(ui_theme::scrollable_theme())
+                .width(Length::Fill)
+                .height(Length::Fill),
+        ]
+        .spacing(10)
+    }
+
+    /// A task from any list with its list, status and due date. Completing
+    /// or editing it writes back to the owning list.
+    fn listed_task_row(&self, task: &ListedTask, today: NaiveDate) -> Element<'_, Message> {
+        let list = task.list.clone();
+        let id = task.entry.id;
+        let mut details = format!("{}  ·  {}", task.list_name, task.entry.status);
+        if let Some(due_date) = task.entry.due_date {
+            details.push_str(&format!(
+                "  ·  Due: {}",
+                due_date.format(self.settings.date_format.pattern())
+            ));
+        }
+        if task.entry.is_overdue(today) {
+            details.push_str("  ·  Overdue");
+        }
+        let blockers = self.open_blockers(&task.entry);
+        if !blockers.is_empty() {
+            details.push_str(&format!("  ·  Blocked by {}", blockers.join(", ")));
+        }
+
+        let completed_box = checkbox("", task.entry.completed, move |checked| {
+            Message::ListedTaskToggled(list.clone(), id, checked)
+        })
+        .style(ui_theme::checkbox_theme());
+
+        let edit_button = button(text("Edit"))
+            .on_press(Message::ListedTaskEditPressed(task.list.clone(), id))
+            .style(ui_theme::button_theme());
+
+        let row = container(
+            row![
+                completed_box,
+                column![text(&task.entry.name), text(details).size(13)]
+                    .spacing(4)
+                    .width(Length::Fill),
+                edit_button,
+            ]
+            .align_items(Alignment::Center)
+            .spacing(10),
+        )
+        .style(ui_theme::container_theme())
+        .width(Length::Fill)
+        .padding(5);
+
+        mouse_area(row)
+            .on_press(Message::ListedTaskOpened(task.list.clone(), id))
+            .into()
+    }
+
+    /// Open tasks from every list grouped by how soon they are due.
+    fn agenda_content(&self) -> Column<'_, Message> {
+        let today = today();
+        let mut sections: BTreeMap<AgendaSection, Vec<&ListedTask>> = BTreeMap::new();
+        for task in &self.mem.all_tasks {
+            if let Some(section) = AgendaSection::of(&task.entry, today, self.settings.week_start) {
+                sections.entry(section).or_default().push(task);
+            }
+        }
+
+        let mut rows = Column::new().spacing(10);
+        for section in AgendaSection::ALL {
+            let Some(tasks) = sections.get_mut(&section) else {
+                continue;
+            };
+            tasks.sort_by_key(|task| (task.entry.due_date, task.entry.name.to_lowercase()));
+            let mut heading = text(format!("{} ({})", section, tasks.len())).size(16);
+            if section == AgendaSection::Overdue {
+                heading = heading.style(self.theme().palette().danger);
+            }
+            rows = rows.push(heading);
+            for task in tasks.iter() {
+                rows = rows.push(self.listed_task_row(task, today));
+            }
+        }
+
+        column![
+            text("Agenda").size(20),
+            Scrollable::new(rows)
+                .style(ui_theme::scrollable_theme())
+                .width(Length::Fill)
+                .height(Length::Fill),
+        ]
+        .spacing(10)
+    }
+
+    /// Names of the unfinished tasks `entry` waits on. Blockers that were
+    /// deleted or archived no longer hold it up.
+    fn open_blockers(&self, entry: &TaskEntry) -> Vec<&str> {
+        entry
+            .blocked_by
+            .iter()
+            .filter_map(|blocker| self.listed_task(blocker))
+            .filter(|task| !task.entry.completed)
+            .map(|task| task.entry.name.as_str())
+            .collect()
+    }
+
+    fn listed_task(&self, task: &TaskRef) -> Option<&ListedTask> {
+        self.mem
+            .all_tasks
+            .iter()
+            .find(|other| other.list == task.list && other.entry.id == task.id)
+    }
+
+    fn task_label(&self, task: &TaskRef) -> String {
+        match self.listed_task(task) {
+            Some(other) if other.list == self.mem.list_name => other.entry.name.clone(),
+            Some(other) => format!("{} ({})", other.entry.name, other.list_name),
+            None => "a removed task".to_string(),
+        }
+    }
+
+    /// Whether `start` waits on `target`, directly or through other tasks.
+    fn depends_on(&self, start: &TaskRef, target: &TaskRef) -> bool {
+        let mut stack = vec![start.clone()];
+        let mut seen = BTreeSet::new();
+        while let Some(current) = stack.pop() {
+            if current == *target {
+                return true;
+            }
+            if !seen.insert(current.clone()) {
+                continue;
+            }
+            if let Some(task) = self.listed_task(&current) {
+                stack.extend(task.entry.blocked_by.iter().cloned());
+            }
+        }
+        false
+    }
+
+    /// Open tasks from every list that the edited task could wait on.
+    fn blocker_choices(&self) -> Vec<BlockerChoice> {
+        let editing = self.mem.editing_task_id.map(|id| TaskRef {
+            list: self.mem.list_name.clone(),
+            id,
+        });
+        self.mem
+            .all_tasks
+            .iter()
+            .filter(|task| !task.entry.completed)
+            .map(|task| TaskRef {
+                list: task.list.clone(),
+                id: task.entry.id,
+            })
+            .filter(|task| Some(task) != editing.as_ref() && !self.mem.task_blockers.contains(task))
+            .map(|task| BlockerChoice {
+                label: self.task_label(&task),
+                task,
+            })
+            .collect()
+    }
+
+    /// Follows tasks moved out of the open list with every reference to them.
+    /// Returns the lists that changed.
+    fn retarget_moved(&mut self, ids: &[i32], target: &str, new_ids: &[i32]) -> Vec<String> {
+        let moved: Vec<(TaskRef, TaskRef)> = ids
+            .iter()
+            .zip(new_ids)
+            .map(|(id, new_id)| {
+                (
+                    TaskRef {
+                        list: self.mem.list_name.clone(),
+                        id: *id,
+                    },
+                    TaskRef {
+                        list: target.to_string(),
+                        id: *new_id,
+                    },
+                )
+            })
+            .collect();
+        json_handling::retarget_dependencies(&moved)
+    }
+
+    /// Whether the open list is shown rather than one of the views that
+    /// collect tasks from every list.
+    fn showing_list(&self) -> bool {
+        self.mem.smart_view.is_none() && self.mem.calendar.is_none() && !self.mem.agenda
+    }
+
+    /// The due date typed into the Create or Edit card.
+    fn parse_task_due(&self) -> Result<Option<NaiveDate>, String> {
+        match self.mem.task_due.trim() {
+            "" => Ok(None),
+            input => NaiveDate::parse_from_str(input, self.settings.date_format.pattern())
+                .map(Some)
+                .map_err(|_| format!("Invalid date, expected {}", self.settings.date_format)),
+        }
+    }
+
+    /// A multi-line Markdown editor made of one input per line. Enter splits
+    /// the line at the cursor, Backspace and Delete at the edges of a line
+    /// join it with its neighbour.
+    fn description_editor(&self) -> Column<'_, Message> {
+        let lines: Vec<&str> = self.mem.task_desc.split('\n').collect();
+        let line_count = lines.len();
+        let removable = line_count > 1;
+        let mut editor = Column::new().spacing(2);
+        for (index, line) in lines.into_iter().enumerate() {
+            let placeholder = if index == 0 {
+                "Description, Markdown is supported"
+            } else {
+                ""
+            };
+            let input = text_input(placeholder, line)
+                .id(description_line_id(index))
+                .on_input(move |input| Message::DescriptionLineChanged(index, input))
+                .style(ui_theme::text_input_theme())
+                .width(Length::Fill);
+            let mut line_input = LineInput::new(input, line, move |start, end| {
+                Message::DescriptionLineSplit(index, start, end)
+            });
+            if index > 0 {
+                line_input = line_input.on_join_previous(Message::DescriptionLinesJoined(index));
+            }
+            if index + 1 < line_count {
+                line_input = line_input.on_join_next(Message::DescriptionLinesJoined(index + 1));
+            }
+            let mut line_row = row![line_input].align_items(Alignment::Center).spacing(5);
+            if removable {
+                line_row = line_row.push(
+                    button(text("×"))
+                        .on_press(Message::DescriptionLineRemoved(index))
+                        .style(ui_theme::button_theme()),
+                );
+            }
+            editor = editor.push(line_row);
+        }
+        editor
+    }
+
+    fn parse_task_estimate(&self) -> Result<Option<Estimate>, String> {
+        match self.mem.task_estimate.trim() {
+            "" => Ok(None),
+            input => Estimate::parse(input).map(Some),
+        }
+    }
+
+    /// Reminders are entered as a date in the chosen format and a time.
+    fn reminder_pattern(&self) -> String {
+        format!("{} %H:%M", self.settings.date_format.pattern())
+    }
+
+    fn parse_task_reminder(&self) -> Result<Option<DateTime<Local>>, String> {
+        let invalid = || {
+            format!(
+                "Invalid reminder, expected {} HH:MM",
+                self.settings.date_format
+            )
+        };
+        match self.mem.task_reminder.trim() {
+            "" => Ok(None),
+            input => NaiveDateTime::parse_from_str(input, &self.reminder_pattern())
+                .ok()
+                .and_then(|time| Local.from_local_datetime(&time).earliest())
+                .map(Some)
+                .ok_or_else(invalid),
+        }
+    }
+
+    /// Everything entered in the Create and Edit cards that needs parsing.
+    fn parse_task_fields(&self) -> Result<TaskFields, String> {
+        Ok(TaskFields {
+            due_date: self.parse_task_due()?,
+            estimate: self.parse_task_estimate()?,
+            remind_at: self.parse_task_reminder()?,
+        })
+    }
+
+    /// Sends a notification for every open task whose reminder is due and
+    /// clears the reminder so it goes off only once.
+    fn check_reminders(&mut self) -> Command<Message> {
+        let now = Local::now();
+        let due: Vec<ListedTask> = self
+            .mem
+            .all_tasks
+            .iter()
+            .filter(|task| {
+                !task.entry.completed && task.entry.remind_at.is_some_and(|at| at <= now)
+            })
+            .cloned()
+            .collect();
+        if due.is_empty() {
+            return Command::none();
+        }
+
+        let pattern = self.reminder_pattern();
+        let mut commands = Vec::new();
+        let mut changed = Vec::new();
+        for mut task in due {
+            let remind_at = task.entry.remind_at.take().unwrap_or(now);
+            // An open Edit card would otherwise save the reminder again.
+            if matches!(self.state, State::Edit)
+                && task.list == self.mem.list_name
+                && self.mem.editing_task_id == Some(task.entry.id)
+            {
+                self.mem.task_reminder = String::new();
+            }
+            let body = if remind_at < self.mem.launched_at {
+                format!(
+                    "{}, missed while Tasks was closed",
+                    remind_at.format(&pattern)
+                )
+            } else {
+                format!("{} in {}", remind_at.format(&pattern), task.list_name)
+            };
+            let task_ref = TaskRef {
+                list: task.list.clone(),
+                id: task.entry.id,
+            };
+            if !self.mem.reminders.contains(&task_ref) {
+                self.mem.reminders.push(task_ref.clone());
+            }
+            commands.push(Command::perform(
+                reminders::notify(
+                    task.entry.name.clone(),
+                    body,
+                    task_ref.clone(),
+                    self.mem.notifications.clone(),
+                ),
+                move |action| Message::ReminderAnswered(task_ref, action),
+            ));
+            self.saved(json_handling::write_task(
+                &Tasks {
+                    tasks: vec![task.entry],
+                    meta: ListMeta::default(),
+                },
+                task.list.clone(),
+            ));
+            changed.push(task.list);
+        }
+        self.refresh_changed(&changed);
+        Command::batch(commands)
+    }
+
+    /// Reminders that went off, with the same actions as the notification.
+    fn reminder_bar(&self) -> Column<'_, Message> {
+        let mut bar = Column::new().spacing(5).width(Length::Fill);
+        for task in &self.mem.reminders {
+            let action = |label: &str, message| {
+                button(text(label))
+                    .on_press(message)
+                    .style(ui_theme::button_theme())
+            };
+            bar = bar.push(
+                container(
+                    row![
+                        text(Icon::Bell.to_string()).font(ICON_FONT),
+                        text(self.task_label(task)).width(Length::Fill),
+                        action(
+                            &format!("Snooze {} min", reminders::SNOOZE_MINUTES),
+                            Message::ReminderAnswered(task.clone(), Some(ReminderAction::Snooze)),
+                        ),
+                        action(
+                            "Mark done",
+                            Message::ReminderAnswered(task.clone(), Some(ReminderAction::Done)),
+                        ),
+                        action("Dismiss", Message::ReminderDismissed(task.clone())),
+                    ]
+                    .align_items(Alignment::Center)
+                    .spacing(10),
+                )
+                .style(ui_theme::focused_container_theme())
+                .padding(5)
+                .width(Length::Fill),
+            );
+        }
+        bar
+    }
+
+    /// Tasks from every list placed on their due dates. Tasks are dragged to
+    /// another day to reschedule them, clicking a free spot of a day opens
+    /// the Create card due that day.
+    fn calendar_content(&self) -> Column<'_, Message> {
+        let span = self.mem.calendar.unwrap_or(CalendarSpan::Month);
+        let anchor = self.mem.calendar_anchor;
+        let week_start = self.settings.week_start;
+        let today = today();
+        let palette = self.theme().palette();
+        let max_per_day = match span {
+            CalendarSpan::Month => 3,
+            CalendarSpan::Week => 12,
+        };
+
+        let mut by_day: BTreeMap<NaiveDate, Vec<&ListedTask>> = BTreeMap::new();
+        for task in &self.mem.all_tasks {
+            if !self.settings.show_completed && task.entry.completed {
+                continue;
+            }
+            if let Some(due_date) = task.entry.due_date {
+                by_day.entry(due_date).or_default().push(task);
+            }
+        }
+
+        let nav = |label: &str, message: Message| {
+            button(text(label).horizontal_alignment(alignment::Horizontal::Center))
+                .on_press(message)
+                .style(ui_theme::button_theme())
+        };
+        let header = row![
+            text(span.title(anchor, week_start, self.settings.date_format.pattern()))
+                .size(20)
+                .width(Length::Fill),
+            nav("<", Message::CalendarStepped(false)),
+            nav("Today", Message::CalendarToday),
+            nav(">", Message::CalendarStepped(true)),
+            pick_list(
+                &CalendarSpan::ALL[..],
+                Some(span),
+                Message::CalendarSpanSelected
+            )
+            .style(ui_theme::pick_list_theme()),
+        ]
+        .align_items(Alignment::Center)
+        .spacing(5);
+
+        let mut weekdays = Row::new().spacing(4);
+        for name in calendar::weekday_names(week_start) {
+            weekdays = weekdays.push(text(name).size(13).width(Length::FillPortion(1)));
+        }
+
+        let days = span.days(anchor, week_start);
+        let mut grid = Column::new().spacing(4).height(Length::Fill);
+        for week in days.chunks(7) {
+            let mut week_row = Row::new().spacing(4).height(Length::FillPortion(1));
+            for day in week {
+                let mut number = text(day.format("%-d").to_string()).size(13);
+                if *day == today {
+                    number = number.style(palette.primary);
+                }
+                let mut cell = Column::new().spacing(2).push(number);
+
+                let tasks = by_day.get(day).map(Vec::as_slice).unwrap_or_default();
+                for task in tasks.iter().take(max_per_day) {
+                    let mut label = text(&task.entry.name).size(12);
+                    if task.entry.completed {
+                        label = label.style(Color {
+                            a: 0.5,
+                            ..palette.text
+                        });
+                    } else if task.entry.is_overdue(today) {
+                        label = label.style(palette.danger);
+                    }
+                    let chip = container(label)
+                        .style(ui_theme::container_theme())
+                        .width(Length::Fill)
+                        .padding([1, 4]);
+                    cell = cell.push(mouse_area(chip).on_press(Message::CalendarTaskPressed(
+                        task.list.clone(),
+                        task.entry.id,
+                    )));
+                }
+                if tasks.len() > max_per_day {
+                    cell = cell.push(text(format!("+{} more", tasks.len() - max_per_day)).size(12));
+                }
+
+                // Days of the neighbouring months are shown dimmed.
+                let outside = span == CalendarSpan::Month && day.month() != anchor.month();
+                let cell = container(cell)
+                    .style(if outside {
+                        iced::theme::Container::Transparent
+                    } else {
+                        ui_theme::container_theme()
+                    })
+                    .width(Length::FillPortion(1))
+                    .height(Length::Fill)
+                    .padding(4);
+                week_row = week_row.push(
+                    mouse_area(cell)
+                        .on_press(Message::CalendarDayPressed(*day))
+                        .on_release(Message::CalendarDayReleased(*day)),
+                );
+            }
+            grid = grid.push(week_row);
+        }
+
+        column![header, weekdays, grid].spacing(10)
+    }
+
+    fn reschedule(&mut self, list: &str, id: i32, day: NaiveDate) {
+        let mut tasks = match json_handling::read_tasks(list.to_string()) {
+            Ok(tasks) => tasks,
+            Err(err) => {
+                self.mem.status = Some(err);
+                return;
+            }
+        };
+        if let Some(task) = tasks.tasks.iter_mut().find(|task| task.id == id) {
+            task.due_date = Some(day);
+            self.saved(json_handling::write_task(&tasks, list.to_string()));
+        }
+        if list == self.mem.list_name {
+            if let Ok(tasks) = json_handling::read_tasks(list.to_string()) {
+                self.mem.task_entries = tasks.tasks;
+            }
+        }
+        self.refresh_list(list);
+    }
+
+    /// The icon, name and description of the open list above the tasks.
+    fn list_header(&self) -> Row<'_, Message> {
+        let meta = &self.mem.list_meta;
+        let accent = meta
+            .color
+            .color()
+            .unwrap_or_else(|| self.theme().palette().primary);
+        let name = if meta.name.is_empty() {
+            self.mem.list_name.clone()
+        } else {
+            meta.name.clone()
+        };
+
+        let icon = text(meta.icon.icon().to_string())
+            .font(ICON_FONT)
+            .size(22)
+            .style(accent);
+        let mut titles = column![text(name).size(20).style(accent)];
+        if !meta.description.is_empty() {
+            titles = titles.push(text(&meta.description).size(13));
+        }
+        let effort = EffortTotals::of(
+            self.mem
+                .all_tasks
+                .iter()
+                .filter(|task| task.list == self.mem.list_name)
+                .map(|task| &task.entry),
+            Local::now(),
+        )
+        .summary();
+        if !effort.is_empty() {
+            titles = titles.push(text(format!("Estimates: {}", effort)).size(12));
+        }
+
+        let mut filters = vec![StatusFilter::All, StatusFilter::Actionable];
+        filters.extend(
+            meta.statuses()
+                .into_iter()
+                .map(|status| StatusFilter::Only(status.name)),
+        );
+        let status_filter = pick_list(
+            filters,
+            Some(self.mem.status_filter.clone()),
+            Message::StatusFilterSelected,
+        )
+        .style(ui_theme::pick_list_theme())
+        .width(130);
+
+        let view_button = button(text(match meta.view {
+            ListView::List => "Board",
+            ListView::Board => "List",
+        }))
+        .on_press(Message::ToggleBoard)
+        .style(ui_theme::button_theme());
+
+        let details_button = button(text("Details"))
+            .on_press(Message::ListDetailsPressed)
+            .style(ui_theme::button_theme());
+
+        row![
+            icon,
+            titles.width(Length::Fill),
+            status_filter,
+            view_button,
+            details_button
+        ]
+        .align_items(Alignment::Center)
+        .spacing(10)
+        .padding([0, 5])
+    }
+
+    /// The task whose timer is running and its open session.
+    fn running_timer(&self) -> Option<(&ListedTask, &TimeSession)> {
+        self.mem.all_tasks.iter().find_map(|task| {
+            task.entry
+                .time_sessions
+                .iter()
+                .find(|session| session.end.is_none())
+                .map(|session| (task, session))
+        })
+    }
+
+    /// Starts or stops the timer of a task. Starting one stops whichever
+    /// timer was running before, in any list.
+    fn toggle_timer(&mut self, list: &str, id: i32) {
+        let now = Local::now();
+        let mut was_running = false;
+        let mut changed = vec![list.to_string()];
+        let running: Vec<ListedTask> = self
+            .mem
+            .all_tasks
+            .iter()
+            .filter(|task| time_tracking::is_running(&task.entry.time_sessions))
+            .cloned()
+            .collect();
+        for mut task in running {
+            if task.list == list && task.entry.id == id {
+                was_running = true;
+            } else {
+                self.mem.status = Some(format!("Stopped the timer on \"{}\"", task.entry.name));
+            }
+            time_tracking::stop(&mut task.entry.time_sessions, now);
+            self.saved(json_handling::write_task(
+                &Tasks {
+                    tasks: vec![task.entry],
+                    meta: ListMeta::default(),
+                },
+                task.list.clone(),
+            ));
+            changed.push(task.list);
+        }
+
+        if !was_running {
+            let Some(mut task) = self
+                .mem
+                .all_tasks
+                .iter()
+                .find(|task| task.list == list && task.entry.id == id)
+                .map(|task| task.entry.clone())
+            else {
+                return;
+            };
+            task.time_sessions.push(TimeSession {
+                start: now,
+                end: None,
+            });
+            self.saved(json_handling::write_task(
+                &Tasks {
+                    tasks: vec![task],
+                    meta: ListMeta::default(),
+                },
+                list.to_string(),
+            ));
+        }
+        self.refresh_changed(&changed);
+    }
+
+    /// Logs a finished pomodoro on the task and moves on to the break, or
+    /// from a break back to work.
+    fn finish_focus_period(&mut self, now: DateTime<Local>) -> Command<Message> {
+        let Some(mut focus) = self.mem.focus.take() else {
+            return Command::none();
+        };
+        let work = chrono::Duration::minutes(i64::from(self.settings.focus_work_minutes));
+        let rest = chrono::Duration::minutes(i64::from(self.settings.focus_break_minutes));
+
+        if focus.phase == FocusPhase::Work {
+            let Some(mut task) = self.listed_task(&focus.task).map(|task| task.entry.clone())
+            else {
+                self.mem.status = Some(format!("\"{}\" no longer exists", focus.name));
+                return self.end_focus();
+            };
+            task.pomodoros.push(now);
+            task.time_sessions.push(TimeSession {
+                start: now - work,
+                end: Some(now),
+            });
+            self.saved(json_handling::write_task(
+                &Tasks {
+                    tasks: vec![task],
+                    meta: ListMeta::default(),
+                },
+                focus.task.list.clone(),
+            ));
+            self.refresh_list(&focus.task.list);
+            focus.finished += 1;
+
+            if focus.complete_at_end {
+                let command = self.update(Message::ListedTaskToggled(
+                    focus.task.list.clone(),
+                    focus.task.id,
+                    true,
+                ));
+                self.mem.status = Some(format!(
+                    "Completed \"{}\" after {} pomodoros",
+                    focus.name, focus.finished
+                ));
+                return Command::batch(vec![command, self.end_focus()]);
+            }
+        }
+
+        focus.advance(work, rest, now);
+        self.mem.focus = Some(focus);
+        Command::none()
+    }
+
+    /// Leaves focus mode and gives the window its size back.
+    fn end_focus(&mut self) -> Command<Message> {
+        self.mem.focus = None;
+        let geometry = &self.mem.window_geometry;
+        if geometry.maximized {
+            window::maximize(true)
+        } else {
+            window::resize(iced::Size::new(geometry.width, geometry.height))
+        }
+    }
+
+    /// The compact layout shown instead of the lists while focusing.
+    fn focus_content(&self) -> Container<'_, Message> {
+        let Some(focus) = &self.mem.focus else {
+            return container(text(""));
+        };
+        let now = Local::now();
+        let accent = match focus.phase {
+            FocusPhase::Work => self.theme().palette().primary,
+            FocusPhase::Break => self.theme().palette().success,
+        };
+        let total = self
+            .listed_task(&focus.task)
+            .map(|task| task.entry.pomodoros.len())
+            .unwrap_or_default();
+
+        let button = |label: &str, message| {
+            Button::new(text(label).horizontal_alignment(alignment::Horizontal::Center))
+                .style(ui_theme::button_theme())
+                .width(80)
+                .on_press(message)
+        };
+        let pause_label = if focus.is_paused() { "Resume" } else { "Pause" };
+        let skip_label = match focus.phase {
+            FocusPhase::Work => "Break",
+            FocusPhase::Break => "Work",
+        };
+
+        container(
+            column![
+                text(&focus.name).size(20),
+                text(focus.phase.to_string()).style(accent),
+                text(focus::format_countdown(focus.remaining(now)))
+                    .size(72)
+                    .style(accent),
+                text(format!(
+                    "{} pomodoros this session, {} on this task",
+                    focus.finished, total
+                ))
+                .size(12),
+                checkbox(
+                    "Complete the task after this pomodoro",
+                    focus.complete_at_end,
+                    Message::FocusCompleteToggled,
+                )
+                .style(ui_theme::checkbox_theme()),
+                row![
+                    button(pause_label, Message::FocusPauseToggled),
+                    button(skip_label, Message::FocusSkipped),
+                    button("Stop", Message::FocusStopped),
+                ]
+                .spacing(5),
+            ]
+            .align_items(Alignment::Center)
+            .spacing(10),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x()
+        .center_y()
+        .padding(10)
+    }
+
+    /// The dates entered in the time report, both ends included.
+    fn report_range(&self) -> Result<(NaiveDate, NaiveDate), String> {
+        let pattern = self.settings.date_format.pattern();
+        let parse = |input: &str| {
+            NaiveDate::parse_from_str(input.trim(), pattern)
+                .map_err(|_| format!("Invalid date, expected {}", self.settings.date_format))
+        };
+        let (from, to) = (parse(&self.mem.report_from)?, parse(&self.mem.report_to)?);
+        if from > to {
+            return Err("The report has to start before it ends".to_string());
+        }
+        Ok((from, to))
+    }
+
+    /// Tracked time summed per task, tag or list between two dates.
+    fn time_report_card(&self) -> Column<'_, Message> {
+        let date_input = |placeholder: &str, value: &str, on_input: fn(String) -> Message| {
+            text_input(placeholder, value)
+                .on_input(on_input)
+                .style(ui_theme::text_input_theme())
+                .width(Length::Fill)
+        };
+        let from_input = date_input(
+            &format!("From ({})", self.settings.date_format),
+            &self.mem.report_from,
+            Message::ReportFromChanged,
+        );
+        let to_input = date_input(
+            &format!("To ({})", self.settings.date_format),
+            &self.mem.report_to,
+            Message::ReportToChanged,
+        );
+        let group_pick = pick_list(
+            &ReportGroup::ALL[..],
+            Some(self.mem.report_group),
+            Message::ReportGroupSelected,
+        )
+        .style(ui_theme::pick_list_theme())
+        .width(120);
+
+        let mut rows = Column::new().spacing(5);
+        let error = match self.report_range() {
+            Ok((from, to)) => {
+                let report = time_tracking::report(
+                    &self.mem.all_tasks,
+                    self.mem.report_group,
+                    from,
+                    to,
+                    Local::now(),
+                );
+                if report.is_empty() {
+                    rows = rows.push(text("No time tracked in this range."));
+                }
+                let mut total = chrono::Duration::zero();
+                for (name, duration) in &report {
+                    total = total + *duration;
+                    rows = rows.push(row![
+                        text(name).width(Length::Fill),
+                        text(time_tracking::format_duration(*duration))
+                    ]);
+                }
+                if !report.is_empty() {
+                    rows = rows.push(row![
+                        text("Total").width(Length::Fill),
+                        text(time_tracking::format_duration(total))
+                    ]);
+                }
+                self.mem.report_error.clone()
+            }
+            Err(err) => Some(err),
+        };
+
+        let export_button =
+            button(text("Export CSV").horizontal_alignment(alignment::Horizontal::Center))
+                .on_press(Message::ExportTimeReport)
+                .style(ui_theme::button_theme())
+                .width(Length::Fill);
+
+        let close_button =
+            button(text("Close").horizontal_alignment(alignment::Horizontal::Center))
+                .on_press(Message::CloseOverlay)
+                .style(ui_theme::button_theme())
+                .width(Length::Fill);
+
+        let mut body = column![
+            row![from_input, to_input, group_pick].spacing(10),
+            Scrollable::new(rows.padding([0, 10, 0, 0]))
+                .style(ui_theme::scrollable_theme())
+                .height(250)
+        ]
+        .spacing(10);
+        if let Some(error) = error {
+            body = body.push(text(error));
+        }
+        body.push(row![export_button, close_button].spacing(10))
+    }
+
+    /// Reads the recorded changes of the task shown in the history panel.
+    fn load_history(&mut self) {
+        let Some(id) = self.mem.history_task_id else {
+            return;
+        };
+        match json_handling::read_history(&self.mem.list_name) {
+            Ok(changes) => {
+                self.mem.history = history::of_task(changes, id);
+                self.mem.history_error = None;
+            }
+            Err(err) => self.mem.history_error = Some(err),
+        }
+    }
+
+    /// The changes of one task, newest first, each field can be set back to
+    /// the value it had before a change.
+    fn history_card(&self) -> Column<'_, Message> {
+        let pattern = format!("{} %H:%M", self.settings.date_format.pattern());
+        let mut changes = Column::new().spacing(8);
+        if self.mem.history.is_empty() {
+            changes = changes.push(text("No changes recorded yet."));
+        }
+        for (index, change) in self.mem.history.iter().enumerate().rev() {
+            let summary = match change.field {
+                history::Field::Created => format!("Created as {}", history::describe(&change.new)),
+                history::Field::Deleted => format!("Deleted {}", history::describe(&change.old)),
+                field => format!(
+                    "{}: {} → {}",
+                    field,
+                    history::describe(&change.old),
+                    history::describe(&change.new)
+                ),
+            };
+            let mut line = row![column![
+                text(change.at.format(&pattern).to_string()).size(12),
+                text(summary)
+            ]
+            .spacing(2)
+            .width(Length::Fill)]
+            .align_items(Alignment::Center)
+            .spacing(10);
+            if change.field.revertible() {
+                line = line.push(
+                    button(text("Revert"))
+                        .on_press(Message::RevertChange(index))
+                        .style(ui_theme::button_theme()),
+                );
+            }
+            changes = changes.push(line);
+        }
+
+        let close_button =
+            button(text("Close").horizontal_alignment(alignment::Horizontal::Center))
+                .on_press(Message::CloseOverlay)
+                .style(ui_theme::button_theme())
+                .width(Length::Fill);
+
+        let mut body = column![Scrollable::new(changes.padding([0, 10, 0, 0]))
+            .style(ui_theme::scrollable_theme())
+            .height(300)]
+        .spacing(10);
+        if let Some(error) = &self.mem.history_error {
+            body = body.push(text(error));
+        }
+        body.push(close_button)
+    }
+
+    fn list_details_card(&self) -> Column<'_, Message> {
+        let draft = &self.mem.details_draft;
+        let setting = |label: &str, control: Element<'static, Message>| {
+            row![text(label).width(Length::FillPortion(1)), control]
+                .align_items(Alignment::Center)
+                .spacing(10)
+        };
+
+        let description_input = text_input("Description", &draft.description)
+            .on_input(Message::DetailsDescriptionChanged)
+            .on_submit(Message::SaveListDetails)
+            .style(ui_theme::text_input_theme())
+            .width(Length::Fill);
+
+        let folder_input = text_input("Folder, e.g. work/clients", &draft.folder)
+            .on_input(Message::DetailsFolderChanged)
+            .on_submit(Message::SaveListDetails)
+            .style(ui_theme::text_input_theme())
+            .width(Length::Fill);
+
+        let statuses_input = text_input(
+            "Statuses, e.g. Todo, Doing, Waiting, Done",
+            &self.mem.statuses_input,
+        )
+        .on_input(Message::DetailsStatusesChanged)
+        .on_submit(Message::SaveListDetails)
+        .style(ui_theme::text_input_theme())
+        .width(Length::Fill);
 
-                    let input = column![
-                        task_name_input,
-                        task_description_input,
-                        tags_input,
-                        row![ok_button, cancel_button].spacing(10),
-                    ]
-                    .spacing(10);
+        let done_pick = pick_list(
+            parse_statuses(&self.mem.statuses_input).unwrap_or_default(),
+            self.mem.done_status_choice.clone(),
+            Message::DetailsDoneStatusSelected,
+        )
+        .placeholder("Last status")
+        .style(ui_theme::pick_list_theme())
+        .width(Length::FillPortion(1));
 
-                    input
-                }
-                ButtonPressed::Edit => {
-                    let task_name_input = text_input("Name", &self.mem.task_name)
-                        .on_input(Message::TaskNameEdited)
-                        .on_submit(Message::TaskEdited(self.mem.editing_task_id.unwrap()))
-                        .style(ui_theme::text_input_theme())
-                        .width(Length::Fill);
+        let color_pick = pick_list(
+            &ListColor::ALL[..],
+            Some(draft.color),
+            Message::DetailsColorSelected,
+        )
+        .style(ui_theme::pick_list_theme())
+        .width(Length::FillPortion(1));
 
-                    let task_description_input = text_input("Description", &self.mem.task_desc)
-                        .on_input(Message::TaskDescEdited)
-                        .on_submit(Message::TaskEdited(self.mem.editing_task_id.unwrap()))
-                        .style(ui_theme::text_input_theme())
-                        .width(Length::Fill);
+        let icon_pick = pick_list(
+            &ListIcon::ALL[..],
+            Some(draft.icon),
+            Message::DetailsIconSelected,
+        )
+        .style(ui_theme::pick_list_theme())
+        .width(Length::FillPortion(1));
 
-                    let tags_input = text_input("Tags", &self.mem.task_tags.join(","))
-                        .on_input(Message::TagsChanged)
-                        .on_submit(Message::TaskEdited(self.mem.editing_task_id.unwrap()))
-                        .style(ui_theme::text_input_theme())
-                        .width(Length::Fill);
+        let sort_pick = pick_list(
+            ListSortChoice::all(),
+            Some(match draft.sort_order {
+                Some(order) => ListSortChoice::Fixed(order),
+                None => ListSortChoice::Settings,
+            }),
+            Message::DetailsSortSelected,
+        )
+        .style(ui_theme::pick_list_theme())
+        .width(Length::FillPortion(1));
 
-                    let ok_button =
-                        button(text("Ok").horizontal_alignment(alignment::Horizontal::Center))
-                            .on_press(Message::TaskEdited(self.mem.editing_task_id.unwrap()))
-                            .style(ui_theme::button_theme())
-                            .width(Length::Fill);
+        let pinned_box = checkbox(
+            "Pin to the top of the list picker",
+            draft.pinned.is_some(),
+            Message::DetailsPinnedToggled,
+        )
+        .style(ui_theme::checkbox_theme());
 
-                    let cancel_button =
-                        button(text("Cancel").horizontal_alignment(alignment::Horizontal::Center))
-                            .on_press(Message::CloseOverlay)
-                            .style(ui_theme::button_theme())
-                            .width(Length::Fill);
+        let ok_button = button(text("Save").horizontal_alignment(alignment::Horizontal::Center))
+            .on_press(Message::SaveListDetails)
+            .style(ui_theme::button_theme())
+            .width(Length::Fill);
 
-                    let input = column![
-                        task_name_input,
-                        task_description_input,
-                        tags_input,
-                        row![ok_button, cancel_button].spacing(10),
+        let cancel_button =
+            button(text("Cancel").horizontal_alignment(alignment::Horizontal::Center))
+                .on_press(Message::CloseOverlay)
+                .style(ui_theme::button_theme())
+                .width(Length::Fill);
+
+        let mut body = column![
+            description_input,
+            folder_input,
+            statuses_input,
+            setting("Done status", done_pick.into()),
+            setting("Color", color_pick.into()),
+            setting("Icon", icon_pick.into()),
+            setting("Sort order", sort_pick.into()),
+            pinned_box,
+        ]
+        .spacing(10);
+        if let Some(error) = &self.mem.list_error {
+            body = body.push(text(error));
+        }
+        body.push(row![ok_button, cancel_button].spacing(10))
+    }
+
+    /// The open list as columns of cards, cards are dragged between columns
+    /// to change their status.
+    fn board(&self) -> Row<'_, Message> {
+        let statuses = self.mem.list_meta.statuses();
+        let mut cards: Vec<Column<'_, Message>> = statuses
+            .iter()
+            .map(|_| Column::new().spacing(8).width(Length::Fill))
+            .collect();
+        let mut counts = vec![0; statuses.len()];
+
+        for entry in self.filtered_entries(true) {
+            let index = board_column(entry, &statuses);
+            counts[index] += 1;
+
+            let blockers = self.open_blockers(entry);
+            let mut name = text(&entry.name);
+            if !blockers.is_empty() {
+                name = name.style(Color {
+                    a: 0.5,
+                    ..self.theme().palette().text
+                });
+            }
+            let mut card = column![name].spacing(4).width(Length::Fill);
+            if !blockers.is_empty() {
+                card = card.push(
+                    row![
+                        text(Icon::Lock.to_string()).font(ICON_FONT).size(12),
+                        text(format!("Blocked by {}", blockers.join(", "))).size(12),
                     ]
-                    .spacing(10);
+                    .spacing(4),
+                );
+            }
+            let mut details = Vec::new();
+            if entry.priority != Priority::None {
+                details.push(format!("Priority: {}", entry.priority));
+            }
+            if let Some(due_date) = entry.due_date {
+                details.push(format!(
+                    "Due: {}",
+                    due_date.format(self.settings.date_format.pattern())
+                ));
+            }
+            if !details.is_empty() {
+                card = card.push(text(details.join("   ")).size(12));
+            }
+            card = card.push(
+                button(text("Edit").size(12))
+                    .on_press(Message::ButtonEditPressed(entry.id))
+                    .style(ui_theme::button_theme()),
+            );
 
-                    input
-                }
-                ButtonPressed::NewFile => {
-                    let list_name_input = text_input("List Name", &self.mem.file_name)
-                        .on_input(Message::NewFileNameChanged)
-                        .on_submit(Message::CreateNewFile)
-                        .style(ui_theme::text_input_theme())
-                        .width(Length::Fill);
+            let card = container(card)
+                .style(if self.mem.selected_tasks.contains(&entry.id) {
+                    ui_theme::selected_container_theme()
+                } else if self.mem.focused_task == Some(entry.id) {
+                    ui_theme::focused_container_theme()
+                } else {
+                    ui_theme::container_theme()
+                })
+                .width(Length::Fill)
+                .padding(6);
+            cards[index] = std::mem::take(&mut cards[index])
+                .push(mouse_area(card).on_press(Message::DragStarted(entry.id)));
+        }
 
-                    let ok_button =
-                        button(text("Ok").horizontal_alignment(alignment::Horizontal::Center))
-                            .on_press(Message::CreateNewFile)
-                            .style(ui_theme::button_theme())
-                            .width(Length::Fill);
+        let mut board = Row::new().spacing(10).height(Length::Fill);
+        for (index, ((name, cards), count)) in statuses.iter().zip(cards).zip(counts).enumerate() {
+            let lane = column![
+                text(format!("{} ({})", name.name, count)).size(15),
+                Scrollable::new(cards.padding([0, 10, 0, 0]))
+                    .style(ui_theme::scrollable_theme())
+                    .height(Length::Fill),
+            ]
+            .spacing(8);
+            // Cards only react to presses, so a release anywhere in the
+            // lane, over a card or not, lands here.
+            board = board.push(
+                mouse_area(
+                    container(lane)
+                        .width(Length::FillPortion(1))
+                        .height(Length::Fill)
+                        .padding(6),
+                )
+                .on_release(Message::BoardDropped(index)),
+            );
+        }
+        board
+    }
 
-                    let cancel_button =
-                        button(text("Cancel").horizontal_alignment(alignment::Horizontal::Center))
-                            .on_press(Message::CloseOverlay)
-                            .style(ui_theme::button_theme())
-                            .width(Length::Fill);
+    /// The sort order of the open list, lists can override the setting.
+    fn sort_order(&self) -> SortOrder {
+        self.mem
+            .list_meta
+            .sort_order
+            .unwrap_or(self.settings.sort_order)
+    }
 
-                    let button_row = Row::new()
-                        .push(ok_button)
-                        .push(cancel_button)
-                        .align_items(Alignment::Center)
-                        .spacing(10);
+    fn manage_list_card(&self) -> Column<'_, Message> {
+        let action = |label: &str, message: Message| {
+            button(text(label).horizontal_alignment(alignment::Horizontal::Center))
+                .on_press(message)
+                .style(ui_theme::button_theme())
+                .width(Length::Fill)
+        };
 
-                    column![list_name_input, button_row].spacing(10).into()
-                }
+        let rename_row = row![
+            text_input("New name", &self.mem.rename_input)
+                .on_input(Message::RenameInputChanged)
+                .on_submit(Message::RenameList)
+                .style(ui_theme::text_input_theme())
+                .width(Length::FillPortion(2)),
+            action("Rename", Message::RenameList),
+        ]
+        .spacing(10);
 
-                ButtonPressed::DeleteList => {
-                    let ok_button =
-                        button(text("Ok").horizontal_alignment(alignment::Horizontal::Center))
-                            .on_press(Message::DeleteList)
-                            .style(ui_theme::button_theme())
-                            .width(Length::Fill);
+        let duplicate_row = row![
+            text_input("Name of the copy", &self.mem.duplicate_input)
+                .on_input(Message::DuplicateInputChanged)
+                .on_submit(Message::DuplicateList)
+                .style(ui_theme::text_input_theme())
+                .width(Length::FillPortion(2)),
+            action("Duplicate", Message::DuplicateList),
+        ]
+        .spacing(10);
 
-                    let cancel_button =
-                        button(text("Cancel").horizontal_alignment(alignment::Horizontal::Center))
-                            .on_press(Message::CloseOverlay)
-                            .style(ui_theme::button_theme())
-                            .width(Length::Fill);
+        let reset_box = checkbox(
+            "Reset completion in the copy",
+            self.mem.duplicate_reset_completed,
+            Message::DuplicateResetToggled,
+        )
+        .style(ui_theme::checkbox_theme());
 
-                    let button_row = Row::new()
-                        .push(ok_button)
-                        .push(cancel_button)
-                        .align_items(Alignment::Center)
-                        .spacing(10);
+        let archived = &self.mem.archived_lists;
+        let restore_row = row![
+            pick_list(
+                archived.clone(),
+                list_info(archived, self.mem.archived_choice.as_deref()),
+                |list| Message::ArchivedListSelected(list.file)
+            )
+            .placeholder("Archived lists")
+            .style(ui_theme::pick_list_theme())
+            .width(Length::FillPortion(2)),
+            action("Restore", Message::RestoreList),
+        ]
+        .spacing(10);
+
+        let mut body = column![
+            rename_row,
+            duplicate_row,
+            reset_box,
+            action("Archive this list", Message::ArchiveList),
+            restore_row,
+        ]
+        .spacing(10);
+        if let Some(error) = &self.mem.list_error {
+            body = body.push(text(error));
+        }
+        body.push(action("Close", Message::CloseOverlay))
+    }
+
+    fn bulk_card(&self) -> Column<'_, Message> {
+        let action = |label: &str, message: Message| {
+            button(text(label).horizontal_alignment(alignment::Horizontal::Center))
+                .on_press(message)
+                .style(ui_theme::button_theme())
+                .width(Length::Fill)
+        };
+
+        let other_lists: Vec<ListInfo> = self
+            .mem
+            .lists
+            .iter()
+            .filter(|list| list.file != self.mem.list_name)
+            .cloned()
+            .collect();
+
+        let status_row = row![
+            action("Complete", Message::BulkSetCompleted(true)),
+            action("Uncomplete", Message::BulkSetCompleted(false)),
+            action("Delete", Message::BulkDelete),
+        ]
+        .spacing(10);
+
+        let tag_row = row![
+            text_input("Tag", &self.mem.bulk_tag)
+                .on_input(Message::BulkTagChanged)
+                .on_submit(Message::BulkAddTag)
+                .style(ui_theme::text_input_theme())
+                .width(Length::Fill),
+            action("Add tag", Message::BulkAddTag),
+            action("Remove tag", Message::BulkRemoveTag),
+        ]
+        .spacing(10);
+
+        let priority_row = row![
+            text("Priority").width(Length::Fill),
+            pick_list(&Priority::ALL[..], None, Message::BulkPrioritySelected)
+                .placeholder("Set priority")
+                .style(ui_theme::pick_list_theme())
+                .width(Length::FillPortion(2)),
+        ]
+        .align_items(Alignment::Center)
+        .spacing(10);
+
+        let due_date_row = row![
+            text_input(
+                &format!("Due date ({})", self.settings.date_format),
+                &self.mem.bulk_due_date
+            )
+            .on_input(Message::BulkDueDateChanged)
+            .on_submit(Message::BulkSetDueDate)
+            .style(ui_theme::text_input_theme())
+            .width(Length::Fill),
+            action("Set due", Message::BulkSetDueDate),
+            action("Clear due", Message::BulkClearDueDate),
+        ]
+        .spacing(10);
+
+        let list_row = row![
+            pick_list(
+                other_lists,
+                list_info(&self.mem.lists, self.mem.bulk_target.as_deref()),
+                |list| Message::BulkTargetSelected(list.file)
+            )
+            .placeholder("Other list")
+            .style(ui_theme::pick_list_theme())
+            .width(Length::Fill),
+            action("Move", Message::BulkMove),
+            action("Copy", Message::BulkCopy),
+        ]
+        .spacing(10);
+
+        let mut body = column![
+            text(format!("{} tasks selected", self.mem.selected_tasks.len())),
+            status_row,
+            tag_row,
+            priority_row,
+            due_date_row,
+            list_row,
+        ]
+        .spacing(10);
+        if let Some(error) = &self.mem.bulk_error {
+            body = body.push(text(error));
+        }
+        body.push(action("Cancel", Message::CloseOverlay))
+    }
+
+    fn open_command_palette(&mut self) -> Command<Message> {
+        if !matches!(self.state, State::None) {
+            return Command::none();
+        }
+
+        let lists = json_handling::get_lists();
+        let mut items = vec![
+            PaletteItem::new("New task", PaletteCommand::NewTask),
+            PaletteItem::new("Open agenda", PaletteCommand::OpenAgenda),
+            PaletteItem::new("Open calendar", PaletteCommand::OpenCalendar),
+            PaletteItem::new("Export list", PaletteCommand::Export),
+            PaletteItem::new("Time report", PaletteCommand::TimeReport),
+            PaletteItem::new("Toggle theme", PaletteCommand::ToggleTheme),
+            PaletteItem::new(
+                if self.settings.show_completed {
+                    "Hide completed tasks"
+                } else {
+                    "Show completed tasks"
+                },
+                PaletteCommand::ToggleCompleted,
+            ),
+        ];
+        for list in &lists {
+            items.push(PaletteItem::new(
+                format!("Switch to list {}", list.name),
+                PaletteCommand::SwitchList(list.file.clone()),
+            ));
+        }
+        for list in &lists {
+            let tasks = json_handling::read_tasks(list.file.clone()).unwrap_or_default();
+            for task in tasks.tasks.iter().filter(|task| !task.name.is_empty()) {
+                items.push(PaletteItem::new(
+                    format!("{} ({})", task.name, list.name),
+                    PaletteCommand::OpenTask {
+                        list: list.file.clone(),
+                        id: task.id,
+                    },
+                ));
+            }
+        }
+        for list in json_handling::get_archived_lists() {
+            items.push(PaletteItem::new(
+                format!("Restore archived list {}", list.name),
+                PaletteCommand::RestoreList(list.file.clone()),
+            ));
+            let tasks = json_handling::read_archived_tasks(&list.file).unwrap_or_default();
+            for task in tasks.tasks.iter().filter(|task| !task.name.is_empty()) {
+                items.push(PaletteItem::new(
+                    format!("{} ({}, archived)", task.name, list.name),
+                    PaletteCommand::RestoreList(list.file.clone()),
+                ));
+            }
+        }
+
+        self.mem.palette_items = items;
+        self.mem.palette_query = String::new();
+        self.mem.palette_selected = 0;
+        self.state = State::CommandPalette;
+        self.button_pressed = Some(ButtonPressed::CommandPalette);
+        text_input::focus(palette_input_id())
+    }
 
-                    column![button_row].into()
+    fn run_palette_command(&mut self, command: PaletteCommand) -> Command<Message> {
+        match command {
+            PaletteCommand::NewTask => self.update(Message::ButtonCreatePressed),
+            PaletteCommand::OpenAgenda => self.update(Message::AgendaOpened),
+            PaletteCommand::OpenCalendar => self.update(Message::CalendarOpened),
+            PaletteCommand::SwitchList(list) => self.update(Message::FileSelected(list)),
+            PaletteCommand::RestoreList(list) => match json_handling::restore_task_file(&list) {
+                Ok(()) => self.update(Message::FileSelected(list)),
+                Err(err) => {
+                    self.mem.status = Some(err);
+                    Command::none()
                 }
-            };
+            },
+            PaletteCommand::TimeReport => self.update(Message::TimeReportPressed),
+            PaletteCommand::Export => {
+                self.mem.status = Some(match json_handling::export_list(&self.mem.list_name) {
+                    Ok(path) => format!("Exported to {}", path.display()),
+                    Err(err) => err,
+                });
+                Command::none()
+            }
+            PaletteCommand::ToggleTheme => {
+                let next = self
+                    .themes
+                    .iter()
+                    .position(|theme| theme.name == self.settings.theme)
+                    .map(|index| (index + 1) % self.themes.len())
+                    .unwrap_or(0);
+                if let Some(theme) = self.themes.get(next) {
+                    self.settings.theme = theme.name.clone();
+                    let mut stored = json_handling::read_settings().unwrap_or_default();
+                    stored.theme = theme.name.clone();
+                    json_handling::write_settings(&stored);
+                }
+                Command::none()
+            }
+            PaletteCommand::ToggleCompleted => {
+                self.settings.show_completed = !self.settings.show_completed;
+                let mut stored = json_handling::read_settings().unwrap_or_default();
+                stored.show_completed = self.settings.show_completed;
+                json_handling::write_settings(&stored);
+                Command::none()
+            }
+            PaletteCommand::OpenTask { list, id } => {
+                let command = self.update(Message::FileSelected(list));
+                self.mem.focused_task = Some(id);
+                Command::batch(vec![command, self.move_focus(0)])
+            }
+        }
+    }
 
-            card(text(head_string), body_string)
-                .width(Length::from(500))
-                .style(CardStyles::Dark)
-        });
+    fn command_palette_card(&self) -> Column<'_, Message> {
+        let query_input = text_input("Type a command or task name", &self.mem.palette_query)
+            .id(palette_input_id())
+            .on_input(Message::PaletteQueryChanged)
+            .on_submit(Message::PaletteSubmitted)
+            .style(ui_theme::text_input_theme())
+            .width(Length::Fill);
 
-        modal(underlay, overlay)
-            .backdrop(Message::CloseOverlay)
-            .on_esc(Message::CloseOverlay)
-            .into()
+        let results = command_palette::matches(&self.mem.palette_items, &self.mem.palette_query)
+            .into_iter()
+            .enumerate()
+            .fold(Column::new().spacing(2), |column, (index, item)| {
+                let label = if index == self.mem.palette_selected {
+                    format!("> {}", item.label)
+                } else {
+                    item.label.clone()
+                };
+                column.push(
+                    button(text(label))
+                        .on_press(Message::PaletteCommandChosen(item.command.clone()))
+                        .style(if index == self.mem.palette_selected {
+                            ui_theme::button_theme()
+                        } else {
+                            iced::theme::Button::Text
+                        })
+                        .width(Length::Fill),
+                )
+            });
+
+        column![query_input, results].spacing(10)
     }
 
-    fn theme(&self) -> Self::Theme {
-        Theme::Dark
+    /// The tasks shown in the list, sorted and with completed tasks left out
+    /// when the user has hidden them.
+    fn visible_entries(&self) -> Vec<&TaskEntry> {
+        self.filtered_entries(self.settings.show_completed)
+    }
+
+    /// The tasks of the list narrowed down by the status filter, sorted.
+    /// The board always passes `show_completed` so its done lane fills up.
+    fn filtered_entries(&self, show_completed: bool) -> Vec<&TaskEntry> {
+        let mut entries = sorted_entries(&self.mem.task_entries, self.sort_order());
+        if !show_completed {
+            entries.retain(|entry| !entry.completed);
+        }
+        match &self.mem.status_filter {
+            StatusFilter::All => {}
+            StatusFilter::Actionable => {
+                entries.retain(|entry| !entry.completed && self.open_blockers(entry).is_empty())
+            }
+            StatusFilter::Only(name) => entries.retain(|entry| entry.status == *name),
+        }
+        entries
+    }
+
+    /// Moves the focused task past its visible neighbour.
+    fn move_focused_task(&mut self, step: i32) -> Command<Message> {
+        let Some(id) = self.mem.focused_task else {
+            return Command::none();
+        };
+        let order: Vec<i32> = self
+            .visible_entries()
+            .iter()
+            .map(|entry| entry.id)
+            .collect();
+        let Some(index) = order.iter().position(|other| *other == id) else {
+            return Command::none();
+        };
+
+        let target = index as i32 + step;
+        if target < 0 || target >= order.len() as i32 {
+            return Command::none();
+        }
+        self.reorder_task(id, order[target as usize]);
+        self.move_focus(0)
+    }
+
+    /// Moves task `id` to the place of task `target` and stores the new
+    /// positions. Reordering switches the list over to the manual order so
+    /// the result is what the user sees, the sort order in the settings is
+    /// left for the other lists.
+    fn reorder_task(&mut self, id: i32, target: i32) {
+        // Work on the whole list so tasks hidden by a search keep their place.
+        let mut tasks = match json_handling::read_tasks(self.mem.list_name.clone()) {
+            Ok(tasks) => tasks,
+            Err(err) => {
+                println!("{}", err);
+                return;
+            }
+        };
+        let mut order: Vec<i32> = sorted_entries(&tasks.tasks, self.sort_order())
+            .iter()
+            .map(|entry| entry.id)
+            .collect();
+        let (Some(from), Some(to)) = (
+            order.iter().position(|other| *other == id),
+            order.iter().position(|other| *other == target),
+        ) else {
+            return;
+        };
+        let moved = order.remove(from);
+        order.insert(to, moved);
+
+        for entry in tasks.tasks.iter_mut() {
+            if let Some(position) = order.iter().position(|other| *other == entry.id) {
+                entry.position = position as u32;
+            }
+        }
+        if self
+            .saved(json_handling::write_task(
+                &tasks,
+                self.mem.list_name.clone(),
+            ))
+            .is_none()
+        {
+            return;
+        }
+
+        for entry in self.mem.task_entries.iter_mut() {
+            if let Some(stored) = tasks.tasks.iter().find(|other| other.id == entry.id) {
+                entry.position = stored.position;
+            }
+        }
+
+        if self.sort_order() != SortOrder::Manual {
+            self.mem.list_meta.sort_order = Some(SortOrder::Manual);
+            self.mem.status = Some(
+                match json_handling::write_list_meta(&self.mem.list_name, &self.mem.list_meta) {
+                    Ok(()) => "This list is now sorted manually".to_string(),
+                    Err(err) => err,
+                },
+            );
+        }
+    }
+
+    /// Moves the focus indicator through the tasks in display order and keeps
+    /// the focused row in view.
+    fn move_focus(&mut self, step: i32) -> Command<Message> {
+        let order: Vec<i32> = self
+            .visible_entries()
+            .iter()
+            .map(|entry| entry.id)
+            .collect();
+        if order.is_empty() {
+            return Command::none();
+        }
+
+        let index = match self
+            .mem
+            .focused_task
+            .and_then(|id| order.iter().position(|other| *other == id))
+        {
+            Some(index) => (index as i32 + step).clamp(0, order.len() as i32 - 1) as usize,
+            None => 0,
+        };
+        self.mem.focused_task = Some(order[index]);
+
+        let offset = if order.len() > 1 {
+            index as f32 / (order.len() - 1) as f32
+        } else {
+            0.0
+        };
+        scrollable::snap_to(task_scrollable_id(), RelativeOffset { x: 0.0, y: offset })
+    }
+
+    fn load_themes() -> Vec<ThemeDefinition> {
+        let mut themes = ui_theme::builtin_themes();
+        for theme in json_handling::read_user_themes() {
+            // User themes may override a built-in theme by reusing its name.
+            themes.retain(|existing| existing.name != theme.name);
+            themes.push(theme);
+        }
+        themes
+    }
+
+    fn remember_last_list(&mut self) {
+        self.settings.last_list = self.mem.list_name.clone();
+
+        let mut stored = json_handling::read_settings().unwrap_or_default();
+        stored.last_list = self.mem.list_name.clone();
+        json_handling::write_settings(&stored);
+    }
+
+    fn settings_card(&self) -> Column<'_, Message> {
+        let draft = &self.mem.settings_draft;
+        let setting = |label: &str, control: Element<'static, Message>| {
+            row![text(label).width(Length::FillPortion(1)), control]
+                .align_items(Alignment::Center)
+                .spacing(10)
+        };
+
+        let theme_names: Vec<String> = self.themes.iter().map(|theme| theme.name.clone()).collect();
+        let theme_pick = pick_list(
+            theme_names,
+            Some(draft.theme.clone()),
+            Message::SettingsThemeChanged,
+        )
+        .style(ui_theme::pick_list_theme())
+        .width(Length::FillPortion(1));
+
+        let default_list_pick = pick_list(
+            self.mem.lists.clone(),
+            list_info(&self.mem.lists, Some(&draft.default_list)),
+            |list| Message::SettingsDefaultListChanged(list.file),
+        )
+        .style(ui_theme::pick_list_theme())
+        .width(Length::FillPortion(1));
+
+        let startup_pick = pick_list(
+            &StartupBehavior::ALL[..],
+            Some(draft.startup),
+            Message::SettingsStartupChanged,
+        )
+        .style(ui_theme::pick_list_theme())
+        .width(Length::FillPortion(1));
+
+        let sort_pick = pick_list(
+            &SortOrder::ALL[..],
+            Some(draft.sort_order),
+            Message::SettingsSortOrderChanged,
+        )
+        .style(ui_theme::pick_list_theme())
+        .width(Length::FillPortion(1));
+
+        let date_format_pick = pick_list(
+            &DateFormat::ALL[..],
+            Some(draft.date_format),
+            Message::SettingsDateFormatChanged,
+        )
+        .style(ui_theme::pick_list_theme())
+        .width(Length::FillPortion(1));
+
+        let week_start_pick = pick_list(
+            &WeekStart::ALL[..],
+            Some(draft.week_start),
+            Message::SettingsWeekStartChanged,
+        )
+        .style(ui_theme::pick_list_theme())
+        .width(Length::FillPortion(1));
+
+        let font_size_input = text_input("16", &self.mem.font_size_input)
+            .on_input(Message::SettingsFontSizeChanged)
+            .on_submit(Message::SettingsSaved)
+            .style(ui_theme::text_input_theme())
+            .width(Length::FillPortion(1));
+
+        let backup_input = text_input("5", &self.mem.backup_retention_input)
+            .on_input(Message::SettingsBackupRetentionChanged)
+            .on_submit(Message::SettingsSaved)
+            .style(ui_theme::text_input_theme())
+            .width(Length::FillPortion(1));
+
+        let focus_work_input = text_input("25", &self.mem.focus_work_input)
+            .on_input(Message::SettingsFocusWorkChanged)
+            .on_submit(Message::SettingsSaved)
+            .style(ui_theme::text_input_theme())
+            .width(Length::FillPortion(1));
+
+        let focus_break_input = text_input("5", &self.mem.focus_break_input)
+            .on_input(Message::SettingsFocusBreakChanged)
+            .on_submit(Message::SettingsSaved)
+            .style(ui_theme::text_input_theme())
+            .width(Length::FillPortion(1));
+
+        let confirm_task = checkbox(
+            "Confirm before deleting a task",
+            draft.confirm_delete_task,
+            Message::SettingsConfirmTaskChanged,
+        )
+        .style(ui_theme::checkbox_theme());
+
+        let confirm_list = checkbox(
+            "Confirm before deleting a list",
+            draft.confirm_delete_list,
+            Message::SettingsConfirmListChanged,
+        )
+        .style(ui_theme::checkbox_theme());
+
+        let ok_button = button(text("Ok").horizontal_alignment(alignment::Horizontal::Center))
+            .on_press(Message::SettingsSaved)
+            .style(ui_theme::button_theme())
+            .width(Length::Fill);
+
+        let cancel_button =
+            button(text("Cancel").horizontal_alignment(alignment::Horizontal::Center))
+                .on_press(Message::CloseOverlay)
+                .style(ui_theme::button_theme())
+                .width(Length::Fill);
+
+        let shortcuts = Action::ALL
+            .iter()
+            .fold(Column::new().spacing(5), |rows, action| {
+                let action = *action;
+                let input = text_input(
+                    "Unbound",
+                    self.mem
+                        .keymap_inputs
+                        .get(&action)
+                        .map(String::as_str)
+                        .unwrap_or_default(),
+                )
+                .on_input(move |input| Message::SettingsKeymapChanged(action, input))
+                .on_submit(Message::SettingsSaved)
+                .style(ui_theme::text_input_theme())
+                .width(Length::FillPortion(1));
+                rows.push(
+                    row![
+                        text(action.to_string()).width(Length::FillPortion(1)),
+                        input
+                    ]
+                    .align_items(Alignment::Center)
+                    .spacing(10),
+                )
+            });
+
+        let options = column![
+            setting("Theme", theme_pick.into()),
+            setting("Default list", default_list_pick.into()),
+            setting("On startup", startup_pick.into()),
+            setting("Sort tasks by", sort_pick.into()),
+            setting("Date format", date_format_pick.into()),
+            setting("Week starts on", week_start_pick.into()),
+            setting("Font size (restart)", font_size_input.into()),
+            setting("Backups kept per list", backup_input.into()),
+            setting("Focus minutes", focus_work_input.into()),
+            setting("Break minutes", focus_break_input.into()),
+            confirm_task,
+            confirm_list,
+            text("Keyboard shortcuts (comma separated, e.g. Ctrl+L)"),
+            shortcuts,
+        ]
+        .spacing(10)
+        .padding([0, 12, 0, 0]);
+
+        column![
+            container(
+                Scrollable::new(options)
+                    .style(ui_theme::scrollable_theme())
+                    .height(Length::Fill)
+            )
+            .max_height(180),
+            row![ok_button, cancel_button].spacing(10),
+        ]
+        .spacing(10)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn since(word: &str) -> Option<NaiveDate> {
+        // A Thursday.
+        let today = date(2026, 10, 15);
+        match SearchFilter::parse(word, today, WeekStart::Monday)? {
+            SearchFilter::CreatedSince(since)
+            | SearchFilter::UpdatedSince(since)
+            | SearchFilter::CompletedSince(since) => Some(since),
+            SearchFilter::Status(_) => None,
+        }
+    }
+
+    #[test]
+    fn search_filters_read_dates() {
+        assert_eq!(since("created:today"), Some(date(2026, 10, 15)));
+        assert_eq!(since("updated:week"), Some(date(2026, 10, 12)));
+        assert_eq!(since("completed:month"), Some(date(2026, 10, 1)));
+        assert_eq!(since("completed:30d"), Some(date(2026, 9, 15)));
+        assert_eq!(since("created:7"), Some(date(2026, 10, 8)));
+    }
+
+    #[test]
+    fn search_filters_follow_the_week_start() {
+        let filter = SearchFilter::parse("completed:week", date(2026, 10, 15), WeekStart::Sunday);
+        assert!(
+            matches!(filter, Some(SearchFilter::CompletedSince(since)) if since == date(2026, 10, 11))
+        );
+    }
+
+    #[test]
+    fn search_filters_read_statuses() {
+        let filter = SearchFilter::parse("status:doi", date(2026, 10, 15), WeekStart::Monday);
+        assert!(matches!(filter, Some(SearchFilter::Status(prefix)) if prefix == "doi"));
+    }
+
+    #[test]
+    fn search_filters_reaching_too_far_back_are_not_filters() {
+        for word in [
+            "created:100000000",
+            "updated:99999999999d",
+            "completed:4294967295",
+        ] {
+            assert!(since(word).is_none(), "{:?} was read as a filter", word);
+        }
+    }
+
+    #[test]
+    fn other_words_are_not_search_filters() {
+        for word in [
+            "groceries",
+            "due:today",
+            "created:soon",
+            "updated:",
+            "http://x",
+            "created:-5",
+            "updated:-5d",
+        ] {
+            assert!(since(word).is_none(), "{:?} was read as a filter", word);
+        }
     }
 }
@@ -0,0 +1,130 @@
+use crate::gui::json_handling::WeekStart;
+use chrono::{Datelike, Duration, Months, NaiveDate};
+
+/// How much of the calendar is shown at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalendarSpan {
+    Month,
+    Week,
+}
+
+impl CalendarSpan {
+    pub const ALL: [CalendarSpan; 2] = [CalendarSpan::Month, CalendarSpan::Week];
+
+    /// The days shown around `anchor`, always whole weeks so the grid lines
+    /// up under the weekday names.
+    pub fn days(&self, anchor: NaiveDate, week_start: WeekStart) -> Vec<NaiveDate> {
+        let (first, last) = match self {
+            CalendarSpan::Month => {
+                let first = anchor.with_day(1).unwrap_or(anchor);
+                let last = first
+                    .checked_add_months(Months::new(1))
+                    .and_then(|next| next.pred_opt())
+                    .unwrap_or(first);
+                (first, last)
+            }
+            CalendarSpan::Week => (anchor, anchor),
+        };
+        let start = start_of_week(first, week_start);
+        let end = start_of_week(last, week_start) + Duration::days(6);
+        start.iter_days().take_while(|day| *day <= end).collect()
+    }
+
+    /// Moves `anchor` one month or week forwards or backwards.
+    pub fn step(&self, anchor: NaiveDate, forward: bool) -> NaiveDate {
+        match (self, forward) {
+            (CalendarSpan::Month, true) => anchor.checked_add_months(Months::new(1)),
+            (CalendarSpan::Month, false) => anchor.checked_sub_months(Months::new(1)),
+            (CalendarSpan::Week, true) => anchor.checked_add_signed(Duration::days(7)),
+            (CalendarSpan::Week, false) => anchor.checked_sub_signed(Duration::days(7)),
+        }
+        .unwrap_or(anchor)
+    }
+
+    pub fn title(&self, anchor: NaiveDate, week_start: WeekStart, pattern: &str) -> String {
+        match self {
+            CalendarSpan::Month => anchor.format("%B %Y").to_string(),
+            CalendarSpan::Week => format!(
+                "Week of {}",
+                start_of_week(anchor, week_start).format(pattern)
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for CalendarSpan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CalendarSpan::Month => write!(f, "Month"),
+            CalendarSpan::Week => write!(f, "Week"),
+        }
+    }
+}
+
+/// The first day of the week that holds `date`.
+pub fn start_of_week(date: NaiveDate, week_start: WeekStart) -> NaiveDate {
+    let offset = match week_start {
+        WeekStart::Monday => date.weekday().num_days_from_monday(),
+        WeekStart::Sunday => date.weekday().num_days_from_sunday(),
+    };
+    date - Duration::days(i64::from(offset))
+}
+
+/// Short weekday names in the order of the calendar columns.
+pub fn weekday_names(week_start: WeekStart) -> [&'static str; 7] {
+    match week_start {
+        WeekStart::Monday => ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+        WeekStart::Sunday => ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn range(days: &[NaiveDate]) -> (NaiveDate, NaiveDate, usize) {
+        (days[0], days[days.len() - 1], days.len())
+    }
+
+    #[test]
+    fn months_are_padded_to_whole_weeks() {
+        let days = CalendarSpan::Month.days(date(2026, 10, 15), WeekStart::Monday);
+        assert_eq!(range(&days), (date(2026, 9, 28), date(2026, 11, 1), 35));
+        let days = CalendarSpan::Month.days(date(2026, 10, 15), WeekStart::Sunday);
+        assert_eq!(range(&days), (date(2026, 9, 27), date(2026, 10, 31), 35));
+    }
+
+    #[test]
+    fn months_that_fill_their_weeks_get_no_padding() {
+        let days = CalendarSpan::Month.days(date(2026, 2, 28), WeekStart::Sunday);
+        assert_eq!(range(&days), (date(2026, 2, 1), date(2026, 2, 28), 28));
+    }
+
+    #[test]
+    fn weeks_hold_the_anchor() {
+        let days = CalendarSpan::Week.days(date(2026, 10, 15), WeekStart::Monday);
+        assert_eq!(range(&days), (date(2026, 10, 12), date(2026, 10, 18), 7));
+        let days = CalendarSpan::Week.days(date(2026, 10, 18), WeekStart::Sunday);
+        assert_eq!(range(&days), (date(2026, 10, 18), date(2026, 10, 24), 7));
+    }
+
+    #[test]
+    fn steps_keep_to_short_months() {
+        assert_eq!(
+            CalendarSpan::Month.step(date(2026, 1, 31), true),
+            date(2026, 2, 28)
+        );
+        assert_eq!(
+            CalendarSpan::Month.step(date(2026, 3, 31), false),
+            date(2026, 2, 28)
+        );
+        assert_eq!(
+            CalendarSpan::Week.step(date(2026, 12, 29), true),
+            date(2027, 1, 5)
+        );
+    }
+}
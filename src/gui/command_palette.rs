@@ -0,0 +1,138 @@
+/// What happens when an entry of the command palette is chosen.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PaletteCommand {
+    NewTask,
+    OpenAgenda,
+    OpenCalendar,
+    SwitchList(String),
+    /// Brings an archived list back, archived lists and their tasks are
+    /// only reachable through the palette and the Manage card.
+    RestoreList(String),
+    Export,
+    TimeReport,
+    ToggleTheme,
+    ToggleCompleted,
+    OpenTask {
+        list: String,
+        id: i32,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct PaletteItem {
+    pub label: String,
+    pub command: PaletteCommand,
+}
+
+impl PaletteItem {
+    pub fn new(label: impl Into<String>, command: PaletteCommand) -> Self {
+        Self {
+            label: label.into(),
+            command,
+        }
+    }
+}
+
+/// How many matches the palette shows at once.
+pub const MAX_RESULTS: usize = 8;
+
+/// Scores `candidate` against `query` as a case-insensitive subsequence match.
+///
+/// Consecutive characters and characters at the start of a word score higher,
+/// so `nt` ranks "New task" above "Switch to list Notes". Returns `None` when
+/// the query is not a subsequence of the candidate.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous_match: Option<usize> = None;
+
+    for wanted in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = (position..candidate.len()).find(|index| candidate[*index] == wanted)?;
+
+        score += 1;
+        if previous_match.is_some_and(|previous| previous + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !candidate[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        // Prefer matches that start early in the candidate.
+        if previous_match.is_none() {
+            score -= found.min(10) as i32;
+        }
+
+        previous_match = Some(found);
+        position = found + 1;
+    }
+
+    Some(score)
+}
+
+/// The best matching items for `query`, best first.
+pub fn matches<'a>(items: &'a [PaletteItem], query: &str) -> Vec<&'a PaletteItem> {
+    let mut scored: Vec<(i32, usize, &PaletteItem)> = items
+        .iter()
+        .enumerate()
+        .filter_map(|(index, item)| {
+            fuzzy_score(query, &item.label).map(|score| (score, index, item))
+        })
+        .collect();
+
+    // Ties keep the order the items were listed in.
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    scored
+        .into_iter()
+        .take(MAX_RESULTS)
+        .map(|(_, _, item)| item)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuzzy_score_needs_the_query_in_order() {
+        assert!(fuzzy_score("nt", "New task").is_some());
+        assert!(fuzzy_score("tn", "New task").is_none());
+        assert!(fuzzy_score("xyz", "New task").is_none());
+        assert_eq!(fuzzy_score("", "New task"), Some(0));
+    }
+
+    #[test]
+    fn fuzzy_score_ignores_case_and_spaces() {
+        assert_eq!(
+            fuzzy_score("N T", "new task"),
+            fuzzy_score("nt", "New Task")
+        );
+    }
+
+    #[test]
+    fn fuzzy_score_prefers_word_starts_and_runs() {
+        assert!(fuzzy_score("nt", "New task") > fuzzy_score("nt", "Switch to list Notes"));
+        assert!(fuzzy_score("tas", "task") > fuzzy_score("tas", "t a s"));
+        assert!(fuzzy_score("list", "list one") > fuzzy_score("list", "my list"));
+    }
+
+    #[test]
+    fn matches_rank_best_first_and_keep_ties_in_order() {
+        let items: Vec<PaletteItem> = ["Switch to list Notes", "New task", "Export", "Notes"]
+            .into_iter()
+            .map(|label| PaletteItem::new(label, PaletteCommand::Export))
+            .collect();
+        let labels: Vec<&str> = matches(&items, "n")
+            .into_iter()
+            .map(|item| item.label.as_str())
+            .collect();
+        assert_eq!(labels, ["New task", "Notes", "Switch to list Notes"]);
+    }
+
+    #[test]
+    fn matches_are_capped() {
+        let items: Vec<PaletteItem> = (0..MAX_RESULTS + 3)
+            .map(|index| PaletteItem::new(format!("Task {}", index), PaletteCommand::NewTask))
+            .collect();
+        assert_eq!(matches(&items, "task").len(), MAX_RESULTS);
+    }
+}
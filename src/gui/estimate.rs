@@ -0,0 +1,257 @@
+use crate::gui::time_tracking;
+use crate::gui::TaskEntry;
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Estimates above these are taken for typos, they also keep the per-list
+/// totals far from overflowing.
+pub const MAX_MINUTES: u32 = 999 * 60;
+pub const MAX_POINTS: u32 = 999;
+
+/// How much work a task is expected to take, either as time or as story
+/// points. Stored the way it is written, e.g. `"1h 30m"` or `"3pt"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Estimate {
+    Minutes(u32),
+    Points(u32),
+}
+
+impl Estimate {
+    /// Reads `30m`, `2h`, `1h 30m`, `1.5h` or `3pt`. Bare numbers are
+    /// rejected since they could mean either, as is anything past
+    /// `MAX_MINUTES` or `MAX_POINTS`.
+    pub fn parse(input: &str) -> Result<Estimate, String> {
+        let invalid = || format!("Invalid estimate \"{}\", use e.g. 30m, 2h or 3pt", input);
+        let compact: String = input
+            .to_lowercase()
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+
+        for suffix in ["points", "point", "pts", "pt", "p"] {
+            if let Some(number) = compact.strip_suffix(suffix) {
+                let points: u32 = number.parse().map_err(|_| invalid())?;
+                if points > MAX_POINTS {
+                    return Err(format!("Estimates go up to {}pt", MAX_POINTS));
+                }
+                return Ok(Estimate::Points(points));
+            }
+        }
+
+        let mut minutes = 0.0;
+        let mut number = String::new();
+        for c in compact.chars() {
+            match c {
+                '0'..='9' | '.' => number.push(c),
+                'h' | 'm' => {
+                    let value: f64 = number.parse().map_err(|_| invalid())?;
+                    minutes += if c == 'h' { value * 60.0 } else { value };
+                    number.clear();
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        if !number.is_empty() || compact.is_empty() {
+            return Err(invalid());
+        }
+        if minutes.round() > f64::from(MAX_MINUTES) {
+            return Err(format!(
+                "Estimates go up to {}",
+                format_minutes(MAX_MINUTES)
+            ));
+        }
+        Ok(Estimate::Minutes(minutes.round() as u32))
+    }
+}
+
+impl std::fmt::Display for Estimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Estimate::Minutes(minutes) => write!(f, "{}", format_minutes(*minutes)),
+            Estimate::Points(points) => write!(f, "{}pt", points),
+        }
+    }
+}
+
+impl TryFrom<String> for Estimate {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Estimate::parse(&value)
+    }
+}
+
+impl From<Estimate> for String {
+    fn from(value: Estimate) -> Self {
+        value.to_string()
+    }
+}
+
+/// Reads the estimate of a stored task. A value that cannot be read, say
+/// one edited by hand, leaves the task without an estimate instead of
+/// failing the whole list.
+pub fn lenient<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Estimate>, D::Error> {
+    let value = Option::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(value
+        .as_ref()
+        .and_then(serde_json::Value::as_str)
+        .and_then(|text| Estimate::parse(text).ok()))
+}
+
+pub fn format_minutes(minutes: u32) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{}m", minutes),
+        (hours, 0) => format!("{}h", hours),
+        (hours, minutes) => format!("{}h {}m", hours, minutes),
+    }
+}
+
+/// Estimates of one list added up, split into open and completed tasks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EffortTotals {
+    pub remaining_minutes: u32,
+    pub completed_minutes: u32,
+    pub remaining_points: u32,
+    pub completed_points: u32,
+    /// Time tracked on tasks with a time estimate, and what those tasks
+    /// were estimated at.
+    pub tracked: Duration,
+    pub tracked_estimate: u32,
+}
+
+impl EffortTotals {
+    pub fn of<'a>(tasks: impl IntoIterator<Item = &'a TaskEntry>, now: DateTime<Local>) -> Self {
+        let mut totals = EffortTotals {
+            remaining_minutes: 0,
+            completed_minutes: 0,
+            remaining_points: 0,
+            completed_points: 0,
+            tracked: Duration::zero(),
+            tracked_estimate: 0,
+        };
+        for task in tasks {
+            match (task.estimate, task.completed) {
+                (Some(Estimate::Minutes(minutes)), completed) => {
+                    if completed {
+                        totals.completed_minutes = totals.completed_minutes.saturating_add(minutes);
+                    } else {
+                        totals.remaining_minutes = totals.remaining_minutes.saturating_add(minutes);
+                    }
+                    let tracked = time_tracking::tracked(&task.time_sessions, now);
+                    if !tracked.is_zero() {
+                        totals.tracked = totals.tracked + tracked;
+                        totals.tracked_estimate = totals.tracked_estimate.saturating_add(minutes);
+                    }
+                }
+                (Some(Estimate::Points(points)), true) => {
+                    totals.completed_points = totals.completed_points.saturating_add(points)
+                }
+                (Some(Estimate::Points(points)), false) => {
+                    totals.remaining_points = totals.remaining_points.saturating_add(points)
+                }
+                (None, _) => {}
+            }
+        }
+        totals
+    }
+
+    /// One line for the list header, empty when nothing is estimated.
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if self.remaining_minutes > 0 || self.completed_minutes > 0 {
+            parts.push(format!(
+                "{} left, {} done",
+                format_minutes(self.remaining_minutes),
+                format_minutes(self.completed_minutes)
+            ));
+        }
+        if self.remaining_points > 0 || self.completed_points > 0 {
+            parts.push(format!(
+                "{}pt left, {}pt done",
+                self.remaining_points, self.completed_points
+            ));
+        }
+        if self.tracked_estimate > 0 {
+            parts.push(format!(
+                "tracked {} of {} estimated",
+                time_tracking::format_duration(self.tracked),
+                format_minutes(self.tracked_estimate)
+            ));
+        }
+        parts.join(" · ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reads_time_and_points() {
+        assert_eq!(Estimate::parse("30m"), Ok(Estimate::Minutes(30)));
+        assert_eq!(Estimate::parse("2h"), Ok(Estimate::Minutes(120)));
+        assert_eq!(Estimate::parse("1h 30m"), Ok(Estimate::Minutes(90)));
+        assert_eq!(Estimate::parse("1.5H"), Ok(Estimate::Minutes(90)));
+        assert_eq!(Estimate::parse("3pt"), Ok(Estimate::Points(3)));
+        assert_eq!(Estimate::parse("5 points"), Ok(Estimate::Points(5)));
+    }
+
+    #[test]
+    fn parse_rejects_ambiguous_and_malformed_input() {
+        for input in ["", "5", "h", "1h5", "1x", "1..5h", "-2h", "pt"] {
+            assert!(Estimate::parse(input).is_err(), "{:?} was accepted", input);
+        }
+    }
+
+    #[test]
+    fn parse_caps_large_estimates() {
+        assert_eq!(Estimate::parse("999h"), Ok(Estimate::Minutes(MAX_MINUTES)));
+        assert!(Estimate::parse("1000h").is_err());
+        assert!(Estimate::parse("99999999999h").is_err());
+        assert_eq!(Estimate::parse("999pt"), Ok(Estimate::Points(MAX_POINTS)));
+        assert!(Estimate::parse("1000pt").is_err());
+        assert!(Estimate::parse("99999999999pt").is_err());
+    }
+
+    #[test]
+    fn estimates_round_trip_through_their_text() {
+        for estimate in [
+            Estimate::Minutes(45),
+            Estimate::Minutes(120),
+            Estimate::Minutes(95),
+            Estimate::Points(8),
+        ] {
+            assert_eq!(Estimate::parse(&estimate.to_string()), Ok(estimate));
+        }
+    }
+
+    #[derive(Deserialize)]
+    struct Stored {
+        #[serde(default, deserialize_with = "lenient")]
+        estimate: Option<Estimate>,
+    }
+
+    #[test]
+    fn unreadable_stored_estimates_become_none() {
+        let read = |json: &str| serde_json::from_str::<Stored>(json).unwrap().estimate;
+        assert_eq!(
+            read(r#"{"estimate": "1h 30m"}"#),
+            Some(Estimate::Minutes(90))
+        );
+        assert_eq!(read(r#"{"estimate": "lots"}"#), None);
+        assert_eq!(read(r#"{"estimate": 5}"#), None);
+        assert_eq!(read(r#"{"estimate": null}"#), None);
+        assert_eq!(read("{}"), None);
+    }
+
+    #[test]
+    fn totals_saturate_instead_of_overflowing() {
+        let task = TaskEntry {
+            estimate: Some(Estimate::Minutes(u32::MAX)),
+            ..TaskEntry::default()
+        };
+        let totals = EffortTotals::of([&task, &task], Local::now());
+        assert_eq!(totals.remaining_minutes, u32::MAX);
+    }
+}
@@ -0,0 +1,89 @@
+use crate::gui::TaskRef;
+use chrono::{DateTime, Duration, Local};
+
+/// Window size while a focus session runs.
+pub const FOCUS_WINDOW_WIDTH: u32 = 360;
+pub const FOCUS_WINDOW_HEIGHT: u32 = 320;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusPhase {
+    Work,
+    Break,
+}
+
+impl std::fmt::Display for FocusPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FocusPhase::Work => write!(f, "Focus"),
+            FocusPhase::Break => write!(f, "Break"),
+        }
+    }
+}
+
+/// A running pomodoro session on one task, alternating between work and
+/// break periods until it is stopped.
+#[derive(Debug, Clone)]
+pub struct Focus {
+    pub task: TaskRef,
+    pub name: String,
+    pub phase: FocusPhase,
+    /// When the current period is over, moved along while paused.
+    ends: DateTime<Local>,
+    /// Time left in the current period while paused.
+    paused: Option<Duration>,
+    /// Complete the task once the current work period is over.
+    pub complete_at_end: bool,
+    /// Pomodoros finished since the session started.
+    pub finished: u32,
+}
+
+impl Focus {
+    pub fn new(task: TaskRef, name: String, work: Duration, now: DateTime<Local>) -> Self {
+        Focus {
+            task,
+            name,
+            phase: FocusPhase::Work,
+            ends: now + work,
+            paused: None,
+            complete_at_end: false,
+            finished: 0,
+        }
+    }
+
+    pub fn remaining(&self, now: DateTime<Local>) -> Duration {
+        self.paused.unwrap_or(self.ends - now).max(Duration::zero())
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.is_some()
+    }
+
+    pub fn toggle_pause(&mut self, now: DateTime<Local>) {
+        match self.paused.take() {
+            Some(left) => self.ends = now + left,
+            None => self.paused = Some(self.remaining(now)),
+        }
+    }
+
+    /// Whether the current period has run out.
+    pub fn is_over(&self, now: DateTime<Local>) -> bool {
+        self.paused.is_none() && now >= self.ends
+    }
+
+    /// Starts the period that follows the current one.
+    pub fn advance(&mut self, work: Duration, rest: Duration, now: DateTime<Local>) {
+        let (phase, length) = match self.phase {
+            FocusPhase::Work => (FocusPhase::Break, rest),
+            FocusPhase::Break => (FocusPhase::Work, work),
+        };
+        self.phase = phase;
+        self.ends = now + length;
+        self.paused = None;
+    }
+}
+
+/// `24:59`, minutes may run past an hour for long periods.
+pub fn format_countdown(duration: Duration) -> String {
+    let seconds = duration.num_seconds().max(0);
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
@@ -0,0 +1,264 @@
+use crate::gui::{Status, TaskEntry};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// One change to one field of a task. The history of a list is only ever
+/// appended to, reverting a change records another change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Change {
+    pub task_id: i32,
+    pub field: Field,
+    pub old: Value,
+    pub new: Value,
+    pub at: DateTime<Local>,
+}
+
+/// The parts of a task the history keeps track of. `Created` and `Deleted`
+/// mark the ends of a task's life and carry its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Field {
+    Created,
+    Deleted,
+    Name,
+    Description,
+    Tags,
+    Status,
+    Completed,
+    Priority,
+    DueDate,
+    Estimate,
+    Reminder,
+    BlockedBy,
+}
+
+impl Field {
+    /// Fields compared between two versions of a task. Positions and
+    /// timestamps change too often to be worth recording.
+    const TRACKED: [Field; 10] = [
+        Field::Name,
+        Field::Description,
+        Field::Tags,
+        Field::Status,
+        Field::Completed,
+        Field::Priority,
+        Field::DueDate,
+        Field::Estimate,
+        Field::Reminder,
+        Field::BlockedBy,
+    ];
+
+    fn value(&self, task: &TaskEntry) -> Value {
+        let value = match self {
+            Field::Created | Field::Deleted | Field::Name => serde_json::to_value(&task.name),
+            Field::Description => serde_json::to_value(&task.description),
+            Field::Tags => serde_json::to_value(&task.tags),
+            Field::Status => serde_json::to_value(&task.status),
+            Field::Completed => serde_json::to_value(task.completed),
+            Field::Priority => serde_json::to_value(task.priority),
+            Field::DueDate => serde_json::to_value(task.due_date),
+            Field::Estimate => serde_json::to_value(task.estimate),
+            Field::Reminder => serde_json::to_value(task.remind_at),
+            Field::BlockedBy => serde_json::to_value(&task.blocked_by),
+        };
+        value.unwrap_or_default()
+    }
+
+    pub fn revertible(&self) -> bool {
+        !matches!(self, Field::Created | Field::Deleted)
+    }
+}
+
+impl std::fmt::Display for Field {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Field::Created => write!(f, "Created"),
+            Field::Deleted => write!(f, "Deleted"),
+            Field::Name => write!(f, "Name"),
+            Field::Description => write!(f, "Description"),
+            Field::Tags => write!(f, "Tags"),
+            Field::Status => write!(f, "Status"),
+            Field::Completed => write!(f, "Completed"),
+            Field::Priority => write!(f, "Priority"),
+            Field::DueDate => write!(f, "Due date"),
+            Field::Estimate => write!(f, "Estimate"),
+            Field::Reminder => write!(f, "Reminder"),
+            Field::BlockedBy => write!(f, "Blocked by"),
+        }
+    }
+}
+
+/// What changed between the stored version of a task and the one about to
+/// be written, `old` is `None` for a new task.
+pub fn diff(old: Option<&TaskEntry>, new: &TaskEntry, at: DateTime<Local>) -> Vec<Change> {
+    let Some(old) = old else {
+        return vec![Change {
+            task_id: new.id,
+            field: Field::Created,
+            old: Value::Null,
+            new: Field::Created.value(new),
+            at,
+        }];
+    };
+
+    Field::TRACKED
+        .iter()
+        .filter_map(|field| {
+            let (before, after) = (field.value(old), field.value(new));
+            (before != after).then_some(Change {
+                task_id: new.id,
+                field: *field,
+                old: before,
+                new: after,
+                at,
+            })
+        })
+        .collect()
+}
+
+pub fn deleted(task: &TaskEntry, at: DateTime<Local>) -> Change {
+    Change {
+        task_id: task.id,
+        field: Field::Deleted,
+        old: Field::Deleted.value(task),
+        new: Value::Null,
+        at,
+    }
+}
+
+/// The changes of the task that currently has `id`, oldest first. Lists
+/// written before ids were kept unique may have handed the id out before,
+/// changes before the latest `Created` entry, or up to the latest `Deleted`
+/// entry when that came later, belong to an older task.
+pub fn of_task(changes: Vec<Change>, id: i32) -> Vec<Change> {
+    let mut own: Vec<Change> = changes
+        .into_iter()
+        .filter(|change| change.task_id == id)
+        .collect();
+    let start = own
+        .iter()
+        .rposition(|change| matches!(change.field, Field::Created | Field::Deleted))
+        .map(|index| match own[index].field {
+            Field::Created => index,
+            _ => index + 1,
+        })
+        .unwrap_or(0);
+    own.drain(..start);
+    own
+}
+
+/// Sets one field of `task` back to a recorded value. Statuses go through
+/// the list's statuses so `completed` stays in step.
+pub fn apply(
+    task: &mut TaskEntry,
+    field: Field,
+    value: &Value,
+    statuses: &[Status],
+) -> Result<(), String> {
+    let value = value.clone();
+    let invalid = |err: serde_json::Error| format!("Cannot restore {}: {}", field, err);
+    match field {
+        Field::Created | Field::Deleted => {
+            return Err(format!("{} cannot be reverted", field));
+        }
+        Field::Name => task.name = serde_json::from_value(value).map_err(invalid)?,
+        Field::Description => task.description = serde_json::from_value(value).map_err(invalid)?,
+        Field::Tags => task.tags = serde_json::from_value(value).map_err(invalid)?,
+        Field::Status => {
+            let name: String = serde_json::from_value(value).map_err(invalid)?;
+            let status = statuses
+                .iter()
+                .find(|status| status.name == name)
+                .ok_or_else(|| format!("The status \"{}\" no longer exists", name))?;
+            task.set_status(status);
+        }
+        Field::Completed => {
+            let completed = serde_json::from_value(value).map_err(invalid)?;
+            task.set_completed(completed, statuses);
+        }
+        Field::Priority => task.priority = serde_json::from_value(value).map_err(invalid)?,
+        Field::DueDate => task.due_date = serde_json::from_value(value).map_err(invalid)?,
+        Field::Estimate => task.estimate = serde_json::from_value(value).map_err(invalid)?,
+        Field::Reminder => task.remind_at = serde_json::from_value(value).map_err(invalid)?,
+        Field::BlockedBy => task.blocked_by = serde_json::from_value(value).map_err(invalid)?,
+    }
+    Ok(())
+}
+
+/// A recorded value as shown in the history panel.
+pub fn describe(value: &Value) -> String {
+    match value {
+        Value::Null => "none".to_string(),
+        Value::String(text) if text.is_empty() => "empty".to_string(),
+        Value::String(text) => text.clone(),
+        Value::Bool(true) => "yes".to_string(),
+        Value::Bool(false) => "no".to_string(),
+        Value::Array(items) if items.is_empty() => "none".to_string(),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| match item {
+                // Blocked-by references, shown by list and id.
+                Value::Object(task) => format!(
+                    "{} #{}",
+                    task.get("list").map(describe).unwrap_or_default(),
+                    task.get("id").map(describe).unwrap_or_default()
+                ),
+                other => describe(other),
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(task_id: i32, field: Field, new: &str) -> Change {
+        Change {
+            task_id,
+            field,
+            old: Value::Null,
+            new: Value::String(new.to_string()),
+            at: Local::now(),
+        }
+    }
+
+    fn names(changes: &[Change]) -> Vec<&str> {
+        changes
+            .iter()
+            .map(|change| change.new.as_str().unwrap_or_default())
+            .collect()
+    }
+
+    #[test]
+    fn of_task_skips_changes_of_an_earlier_task_with_the_same_id() {
+        let changes = vec![
+            change(1, Field::Created, "old"),
+            change(1, Field::Name, "old renamed"),
+            change(2, Field::Name, "other"),
+            change(1, Field::Deleted, "deleted"),
+            change(1, Field::Created, "new"),
+            change(1, Field::Name, "new renamed"),
+        ];
+        assert_eq!(names(&of_task(changes, 1)), ["new", "new renamed"]);
+    }
+
+    #[test]
+    fn of_task_starts_after_a_deletion_without_a_later_creation() {
+        let changes = vec![
+            change(1, Field::Name, "old"),
+            change(1, Field::Deleted, "deleted"),
+            change(1, Field::Name, "new"),
+        ];
+        assert_eq!(names(&of_task(changes, 1)), ["new"]);
+    }
+
+    #[test]
+    fn of_task_keeps_everything_without_creation_or_deletion() {
+        let changes = vec![change(1, Field::Name, "a"), change(1, Field::Tags, "b")];
+        assert_eq!(names(&of_task(changes, 1)), ["a", "b"]);
+    }
+}
@@ -1,17 +1,45 @@
-use crate::gui::Tasks;
+use crate::gui::history::{self, Change};
+use crate::gui::keymap::{self, Keymap};
+use crate::gui::ui_theme::ThemeDefinition;
+use crate::gui::{ListColor, ListIcon, ListMeta, TaskEntry, TaskRef, Tasks};
+use chrono::{DateTime, Local};
 use dirs::home_dir;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 use std::fs::OpenOptions;
 use std::fs::{self, File};
 use std::io::{BufReader, Read, Seek, SeekFrom, Write};
 use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Settings {
     pub run: bool,
     pub x: i32,
     pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub maximized: bool,
+    pub theme: String,
+    pub default_list: String,
+    pub last_list: String,
+    pub startup: StartupBehavior,
+    pub sort_order: SortOrder,
+    pub confirm_delete_task: bool,
+    pub confirm_delete_list: bool,
+    pub date_format: DateFormat,
+    pub week_start: WeekStart,
+    pub font_size: u16,
+    pub backup_retention: usize,
+    pub show_completed: bool,
+    pub keymap: Keymap,
+    pub sidebar_collapsed: bool,
+    /// Folders folded away in the sidebar, by path.
+    pub collapsed_folders: BTreeSet<String>,
+    /// Lengths of the work and break periods in focus mode.
+    pub focus_work_minutes: u32,
+    pub focus_break_minutes: u32,
 }
 
 impl Default for Settings {
@@ -20,6 +48,154 @@ impl Default for Settings {
             run: false,
             x: 100,
             y: 100,
+            width: 800,
+            height: 335,
+            maximized: false,
+            theme: "Dark".to_string(),
+            default_list: "tasklist".to_string(),
+            last_list: "tasklist".to_string(),
+            startup: StartupBehavior::DefaultList,
+            sort_order: SortOrder::Manual,
+            confirm_delete_task: false,
+            confirm_delete_list: true,
+            date_format: DateFormat::Iso,
+            week_start: WeekStart::Monday,
+            font_size: 16,
+            backup_retention: 5,
+            show_completed: true,
+            keymap: keymap::default_keymap(),
+            sidebar_collapsed: false,
+            collapsed_folders: BTreeSet::new(),
+            focus_work_minutes: 25,
+            focus_break_minutes: 5,
+        }
+    }
+}
+
+impl Settings {
+    /// The list that should be opened when the app starts.
+    pub fn startup_list(&self) -> String {
+        let list = match self.startup {
+            StartupBehavior::DefaultList => &self.default_list,
+            // The agenda is shown over the last list.
+            StartupBehavior::LastList | StartupBehavior::Agenda => &self.last_list,
+        };
+        if get_path(&(list.clone() + ".json")).exists() {
+            list.clone()
+        } else {
+            self.default_list.clone()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StartupBehavior {
+    DefaultList,
+    LastList,
+    Agenda,
+}
+
+impl StartupBehavior {
+    pub const ALL: [StartupBehavior; 3] = [
+        StartupBehavior::DefaultList,
+        StartupBehavior::LastList,
+        StartupBehavior::Agenda,
+    ];
+}
+
+impl std::fmt::Display for StartupBehavior {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StartupBehavior::DefaultList => write!(f, "Open default list"),
+            StartupBehavior::LastList => write!(f, "Open last used list"),
+            StartupBehavior::Agenda => write!(f, "Show the agenda"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortOrder {
+    Manual,
+    Created,
+    Name,
+    OpenFirst,
+    RecentlyUpdated,
+    RecentlyCompleted,
+}
+
+impl SortOrder {
+    pub const ALL: [SortOrder; 6] = [
+        SortOrder::Manual,
+        SortOrder::Created,
+        SortOrder::Name,
+        SortOrder::OpenFirst,
+        SortOrder::RecentlyUpdated,
+        SortOrder::RecentlyCompleted,
+    ];
+}
+
+impl std::fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SortOrder::Manual => write!(f, "Manual"),
+            SortOrder::Created => write!(f, "Created"),
+            SortOrder::Name => write!(f, "Name"),
+            SortOrder::OpenFirst => write!(f, "Open first"),
+            SortOrder::RecentlyUpdated => write!(f, "Recently updated"),
+            SortOrder::RecentlyCompleted => write!(f, "Recently completed"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DateFormat {
+    Iso,
+    DayMonthYear,
+    MonthDayYear,
+}
+
+impl DateFormat {
+    pub const ALL: [DateFormat; 3] = [
+        DateFormat::Iso,
+        DateFormat::DayMonthYear,
+        DateFormat::MonthDayYear,
+    ];
+
+    /// The `chrono` format string used to show and parse dates.
+    pub fn pattern(&self) -> &'static str {
+        match self {
+            DateFormat::Iso => "%Y-%m-%d",
+            DateFormat::DayMonthYear => "%d/%m/%Y",
+            DateFormat::MonthDayYear => "%m/%d/%Y",
+        }
+    }
+}
+
+impl std::fmt::Display for DateFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DateFormat::Iso => write!(f, "YYYY-MM-DD"),
+            DateFormat::DayMonthYear => write!(f, "DD/MM/YYYY"),
+            DateFormat::MonthDayYear => write!(f, "MM/DD/YYYY"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WeekStart {
+    Monday,
+    Sunday,
+}
+
+impl WeekStart {
+    pub const ALL: [WeekStart; 2] = [WeekStart::Monday, WeekStart::Sunday];
+}
+
+impl std::fmt::Display for WeekStart {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WeekStart::Monday => write!(f, "Monday"),
+            WeekStart::Sunday => write!(f, "Sunday"),
         }
     }
 }
@@ -38,10 +214,10 @@ pub fn get_path(input: &str) -> PathBuf {
 fn check_file_exists(path: &PathBuf) {
     if !path.exists() {
         if path.is_file() {
-            File::create(&path).expect("Failed to create file");
+            File::create(path).expect("Failed to create file");
         } else {
             fs::create_dir_all(path.parent().unwrap()).expect("Failed to create directory");
-            File::create(&path).expect("Failed to create file");
+            File::create(path).expect("Failed to create file");
         }
     }
 }
@@ -66,10 +242,17 @@ pub fn read_tasks(name: String) -> Result<Tasks, String> {
         match reader.read_to_string(&mut contents) {
             Ok(_) => {
                 if contents.is_empty() {
-                    Ok(Tasks { tasks: vec![] })
+                    Ok(Tasks {
+                        tasks: vec![],
+                        meta: ListMeta::default(),
+                    })
                 } else {
                     match serde_json::from_str(&contents) {
-                        Ok(tasks) => Ok(tasks),
+                        Ok(mut tasks) => {
+                            normalize_positions(&mut tasks);
+                            normalize_statuses(&mut tasks);
+                            Ok(tasks)
+                        }
                         Err(err) => Err(format!("Failed to parse JSON: {}", err)),
                     }
                 }
@@ -77,71 +260,171 @@ pub fn read_tasks(name: String) -> Result<Tasks, String> {
             Err(err) => Err(format!("Failed to read file: {}", err)),
         }
     } else {
-        Ok(Tasks { tasks: vec![] })
+        Ok(Tasks {
+            tasks: vec![],
+            meta: ListMeta::default(),
+        })
+    }
+}
+
+/// Keeps the stored task order in step with the manual positions.
+///
+/// Lists written before positions existed have every task at 0, the stable
+/// sort keeps them in file order. Positions are then renumbered so deletes
+/// leave no gaps.
+fn normalize_positions(tasks: &mut Tasks) {
+    tasks.tasks.sort_by_key(|task| task.position);
+    for (index, task) in tasks.tasks.iter_mut().enumerate() {
+        task.position = index as u32;
+    }
+}
+
+/// Gives every task one of its list's statuses. Lists written before
+/// statuses existed only know `completed`, those tasks land in the done
+/// status or the first open one. The same happens to tasks whose status was
+/// removed from the list or no longer agrees with `completed`.
+fn normalize_statuses(tasks: &mut Tasks) {
+    let statuses = tasks.meta.statuses();
+    for task in tasks.tasks.iter_mut() {
+        let known = statuses
+            .iter()
+            .any(|status| status.name == task.status && status.done == task.completed);
+        if !known {
+            if let Some(status) = statuses.iter().find(|status| status.done == task.completed) {
+                task.set_status(status);
+            }
+        }
+    }
+}
+
+/// Brings the timestamps of a task that is about to be written up to date.
+/// `existing` is the stored version, `None` for a new task. Changing only the
+/// manual position or the tracked time and pomodoros does not count as an
+/// update.
+fn stamp_task(task: &mut TaskEntry, existing: Option<&TaskEntry>, now: DateTime<Local>) {
+    match existing {
+        None => {
+            task.created_at.get_or_insert(now);
+            task.updated_at.get_or_insert(now);
+        }
+        Some(existing) => {
+            task.created_at = task.created_at.or(existing.created_at);
+            let unchanged = TaskEntry {
+                position: existing.position,
+                time_sessions: existing.time_sessions.clone(),
+                pomodoros: existing.pomodoros.clone(),
+                ..task.clone()
+            } == *existing;
+            if !unchanged {
+                task.updated_at = Some(now);
+            }
+        }
+    }
+
+    if !task.completed {
+        task.completed_at = None;
+    } else if task.completed_at.is_none() || existing.is_some_and(|existing| !existing.completed) {
+        task.completed_at = Some(now);
     }
 }
 
-pub fn write_task(task_list: &Tasks, list_name: String) {
+pub fn write_task(task_list: &Tasks, list_name: String) -> Result<(), String> {
     let name = list_name.clone() + ".json";
     let path = get_path(&name);
     check_file_exists(&path);
 
-    let mut tasks = read_tasks(list_name.clone()).unwrap_or_else(|_| Tasks { tasks: vec![] });
+    // Writing over a list that cannot be read would lose everything in it
+    // but the tasks at hand.
+    let mut tasks = read_tasks(list_name.clone())
+        .map_err(|err| format!("Not saving to {}: {}", list_display_name(&list_name), err))?;
 
     let mut unique_ids: HashSet<i32> = HashSet::new();
 
+    let now = Local::now();
+    let mut changes = Vec::new();
     for task in &task_list.tasks {
         if let Some(existing_task) = tasks.tasks.iter_mut().find(|t| t.id == task.id) {
-            if existing_task.name != task.name
-                || existing_task.description != task.description
-                || existing_task.completed != task.completed
-                || existing_task.tags != task.tags
-            {
-                *existing_task = task.clone();
+            if existing_task != task {
+                let mut task = task.clone();
+                stamp_task(&mut task, Some(existing_task), now);
+                changes.extend(history::diff(Some(existing_task), &task, now));
+                *existing_task = task;
             }
         } else {
-            tasks.tasks.push(task.clone());
+            let mut task = task.clone();
+            stamp_task(&mut task, None, now);
+            changes.extend(history::diff(None, &task, now));
+            tasks.tasks.push(task);
         }
         unique_ids.insert(task.id);
     }
+    tasks.meta.next_id = tasks.next_id();
 
     let json_str = serde_json::to_string_pretty(&tasks)
         .unwrap_or_else(|_| String::from("Failed to serialize tasks"));
-    ();
+    backup_list(&list_name);
 
     let mut file = OpenOptions::new()
         .write(true)
         .truncate(true)
         .open(&path)
-        .expect("Failed to open/write file");
+        .map_err(|err| format!("Failed to open file: {}", err))?;
     file.write_all(json_str.as_bytes())
-        .expect("Failed to write to file");
+        .map_err(|err| format!("Failed to write file: {}", err))?;
+    append_history(&list_name, &changes);
+    Ok(())
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct WindowPosition {
-    pub(crate) x: i32,
-    pub(crate) y: i32,
+fn history_path(list_name: &str) -> PathBuf {
+    let mut path = get_path("history");
+    path.push(list_name.to_string() + ".jsonl");
+    path
 }
 
-pub fn write_settings(state: bool, pos: WindowPosition) {
+/// Adds changes to the end of a list's history in the `history` folder, one
+/// JSON object per line. Earlier lines are never rewritten.
+fn append_history(list_name: &str, changes: &[Change]) {
+    if changes.is_empty() || fs::create_dir_all(get_path("history")).is_err() {
+        return;
+    }
+    let Ok(mut file) = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(history_path(list_name))
+    else {
+        return;
+    };
+    for change in changes {
+        if let Ok(line) = serde_json::to_string(change) {
+            let _ = writeln!(file, "{}", line);
+        }
+    }
+}
+
+/// The recorded changes of a list, oldest first. Lines that cannot be read
+/// are skipped.
+pub fn read_history(list_name: &str) -> Result<Vec<Change>, String> {
+    let path = history_path(list_name);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents =
+        fs::read_to_string(&path).map_err(|err| format!("Failed to read history: {}", err))?;
+    Ok(contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+pub fn write_settings(settings: &Settings) {
     let path = get_path("settings.json");
     check_file_exists(&path);
 
-    let mut settings = read_settings().unwrap_or(Settings {
-        run: false,
-        x: 100,
-        y: 100,
-    });
-    settings.run = state;
-    settings.x = pos.x;
-    settings.y = pos.y;
-
     let mut file = OpenOptions::new()
         .write(true)
         .open(&path)
         .expect("Failed to open/write file");
-    let json_str = serde_json::to_string_pretty(&settings);
+    let json_str = serde_json::to_string_pretty(settings);
     file.set_len(0).expect("Failed to truncate file");
     file.seek(SeekFrom::Start(0))
         .expect("Failed to seek to start of file");
@@ -154,7 +437,7 @@ pub fn read_settings() -> Result<Settings, String> {
 
     if !path.exists() {
         create_dir();
-        write_settings(false, WindowPosition { x: 100, y: 100 });
+        write_settings(&Settings::default());
     }
 
     let file = match File::open(&path) {
@@ -173,41 +456,638 @@ pub fn read_settings() -> Result<Settings, String> {
     }
 }
 
-pub fn delete_tasks(id: i32, file_name: String) {
-    let mut tasks = read_tasks(file_name.clone()).unwrap_or(Tasks { tasks: vec![] });
+/// Loads the user's themes from the `themes` folder. Each `.toml` or `.json`
+/// file holds a single theme; files that fail to parse are skipped.
+pub fn read_user_themes() -> Vec<ThemeDefinition> {
+    let dir = match fs::read_dir(get_path("themes")) {
+        Ok(dir) => dir,
+        Err(_) => return vec![],
+    };
+
+    let mut themes = Vec::new();
+    for entry in dir.flatten() {
+        let path = entry.path();
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(_) => continue,
+        };
 
-    tasks.tasks.retain(|task| task.id != id);
+        let parsed = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => toml::from_str::<ThemeDefinition>(&contents).map_err(|e| e.to_string()),
+            Some("json") => {
+                serde_json::from_str::<ThemeDefinition>(&contents).map_err(|e| e.to_string())
+            }
+            _ => continue,
+        };
 
-    // Reassign IDs based on current index
-    for (index, task) in tasks.tasks.iter_mut().enumerate() {
-        task.id = index as i32;
+        match parsed.and_then(|theme| theme.palette().map(|_| theme)) {
+            Ok(theme) => themes.push(theme),
+            Err(err) => println!("Skipping theme {}: {}", path.display(), err),
+        }
+    }
+
+    themes.sort_by(|a, b| a.name.cmp(&b.name));
+    themes
+}
+
+/// Stores the window geometry without touching the user's other preferences.
+pub fn write_window_geometry(geometry: &WindowGeometry) {
+    let mut settings = read_settings().unwrap_or_default();
+    settings.maximized = geometry.maximized;
+    if !geometry.maximized {
+        settings.x = geometry.x;
+        settings.y = geometry.y;
+        settings.width = geometry.width;
+        settings.height = geometry.height;
+    }
+    write_settings(&settings);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub maximized: bool,
+}
+
+/// Copies the current contents of a list into the backups folder before it
+/// gets overwritten, keeping at most `backup_retention` copies per list.
+fn backup_list(list_name: &str) {
+    let retention = read_settings().unwrap_or_default().backup_retention;
+    let path = get_path(&(list_name.to_string() + ".json"));
+    if retention == 0 || !path.exists() {
+        return;
+    }
+
+    let backup_dir = get_path("backups");
+    if fs::create_dir_all(&backup_dir).is_err() {
+        return;
+    }
+
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or_default();
+    let mut backup_path = backup_dir.clone();
+    backup_path.push(format!("{}.{}.json", list_name, stamp));
+    if fs::copy(&path, &backup_path).is_err() {
+        return;
+    }
+
+    let prefix = format!("{}.", list_name);
+    let mut backups: Vec<(u128, PathBuf)> = match fs::read_dir(&backup_dir) {
+        Ok(dir) => dir
+            .flatten()
+            .filter_map(|entry| {
+                let file_name = entry.file_name().to_str()?.to_string();
+                let stamp = file_name
+                    .strip_prefix(&prefix)?
+                    .strip_suffix(".json")?
+                    .parse::<u128>()
+                    .ok()?;
+                Some((stamp, entry.path()))
+            })
+            .collect(),
+        Err(_) => return,
+    };
+
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.0));
+    for (_, old_backup) in backups.into_iter().skip(retention) {
+        let _ = fs::remove_file(old_backup);
     }
+}
+
+/// Removes tasks from a list and every reference to them. Returns the lists
+/// that changed, `file_name` first.
+pub fn delete_tasks(ids: &[i32], file_name: String) -> Result<Vec<String>, String> {
+    let mut tasks = read_tasks(file_name.clone()).map_err(|err| {
+        format!(
+            "Not deleting from {}: {}",
+            list_display_name(&file_name),
+            err
+        )
+    })?;
+
+    // Ids stay as they are, other tasks refer to them in their blocked-by
+    // lists. The counter is stored first so the deleted ids are not handed
+    // out again.
+    tasks.meta.next_id = tasks.next_id();
+    let now = Local::now();
+    let changes: Vec<Change> = tasks
+        .tasks
+        .iter()
+        .filter(|task| ids.contains(&task.id))
+        .map(|task| history::deleted(task, now))
+        .collect();
+    tasks.tasks.retain(|task| !ids.contains(&task.id));
 
     let name = file_name.clone() + ".json";
     let path = get_path(&name);
+    backup_list(&file_name);
 
-    let json_str = serde_json::to_string_pretty(&tasks);
+    let json_str = serde_json::to_string_pretty(&tasks)
+        .map_err(|err| format!("Failed to serialize tasks: {}", err))?;
     let mut file = OpenOptions::new()
         .write(true)
+        .truncate(true)
         .open(&path)
-        .expect("Failed to open/write file");
-    file.set_len(0).expect("Failed to truncate file");
-    file.seek(SeekFrom::Start(0))
-        .expect("Failed to seek to start of file");
-    file.write_all(json_str.unwrap().as_bytes())
-        .expect("Failed to write to file");
+        .map_err(|err| format!("Failed to open file: {}", err))?;
+    file.write_all(json_str.as_bytes())
+        .map_err(|err| format!("Failed to write file: {}", err))?;
+    append_history(&file_name, &changes);
+
+    let deleted: Vec<TaskRef> = ids
+        .iter()
+        .map(|id| TaskRef {
+            list: file_name.clone(),
+            id: *id,
+        })
+        .collect();
+    let mut changed = vec![file_name.clone()];
+    changed.extend(
+        drop_dependencies(&deleted)
+            .into_iter()
+            .filter(|list| *list != file_name),
+    );
+    Ok(changed)
 }
 
-pub fn create_new_task_file(name: String) {
-    let path_name = name + ".json";
-    let path = get_path(&path_name);
-    File::create(&path).expect("Failed to create file");
+/// Appends copies of `entries` to another list. Every copy gets a fresh id
+/// and goes to the end of the manual order so nothing in the destination
+/// gets overwritten. Unless the tasks are `moving`, the originals stay and
+/// keep their reminder and running timer. Returns the ids of the copies in
+/// the order of `entries`.
+pub fn copy_tasks(
+    entries: &[TaskEntry],
+    list_name: &str,
+    moving: bool,
+) -> Result<Vec<i32>, String> {
+    let mut tasks = read_tasks(list_name.to_string())?;
+    let first_id = tasks.next_id();
+    let first_position = tasks
+        .tasks
+        .iter()
+        .map(|task| task.position + 1)
+        .max()
+        .unwrap_or(0);
+
+    let mut ids = Vec::with_capacity(entries.len());
+    for (offset, entry) in entries.iter().enumerate() {
+        let mut copy = if moving {
+            entry.clone()
+        } else {
+            separate_copy(entry)
+        };
+        copy.id = first_id + offset as i32;
+        copy.position = first_position + offset as u32;
+        ids.push(copy.id);
+        tasks.tasks.push(copy);
+    }
+
+    write_task(&tasks, list_name.to_string())?;
+    Ok(ids)
 }
 
-pub fn delete_task_file(name: String) {
-    let path_name = name + ".json";
+/// A copy of a task that stays next to the original. The reminder would go
+/// off twice and a running timer would count the same time twice, so both
+/// stay with the original only.
+fn separate_copy(entry: &TaskEntry) -> TaskEntry {
+    let mut copy = entry.clone();
+    copy.remind_at = None;
+    copy.time_sessions.retain(|session| session.end.is_some());
+    copy
+}
+
+/// Points blocked-by references at the new place of tasks that were moved
+/// to another list, in every active list. Returns the lists that changed.
+pub fn retarget_dependencies(moved: &[(TaskRef, TaskRef)]) -> Vec<String> {
+    rewrite_dependencies(|blocker| {
+        moved
+            .iter()
+            .find(|(from, _)| from == blocker)
+            .map(|(_, to)| Some(to.clone()))
+    })
+}
+
+/// Removes blocked-by references to deleted tasks from every active list.
+fn drop_dependencies(deleted: &[TaskRef]) -> Vec<String> {
+    rewrite_dependencies(|blocker| deleted.contains(blocker).then_some(None))
+}
+
+/// The update for `rewrite_dependencies` that drops references to any task
+/// of `list`, also to tasks deleted from it earlier.
+fn dropping_list(list: &str) -> impl Fn(&TaskRef) -> Option<Option<TaskRef>> + '_ {
+    move |blocker| (blocker.list == list).then_some(None)
+}
+
+/// Runs `update` on every blocked-by reference in the active lists. It
+/// returns `None` to keep a reference, `Some(None)` to drop it and
+/// `Some(Some(task))` to point it at another task. Returns the lists that
+/// changed.
+fn rewrite_dependencies(update: impl Fn(&TaskRef) -> Option<Option<TaskRef>>) -> Vec<String> {
+    let mut changed_lists = Vec::new();
+    for file in get_files().unwrap_or_default() {
+        let Ok(mut tasks) = read_tasks(file.clone()) else {
+            continue;
+        };
+        if rewrite_blockers(&mut tasks, &update) && write_task(&tasks, file.clone()).is_ok() {
+            changed_lists.push(file);
+        }
+    }
+    changed_lists
+}
+
+/// `rewrite_dependencies` for the tasks of one list. Returns whether any
+/// reference changed.
+fn rewrite_blockers(
+    tasks: &mut Tasks,
+    update: &impl Fn(&TaskRef) -> Option<Option<TaskRef>>,
+) -> bool {
+    let mut changed = false;
+    for task in tasks.tasks.iter_mut() {
+        let mut blocked_by = Vec::with_capacity(task.blocked_by.len());
+        for blocker in task.blocked_by.drain(..) {
+            match update(&blocker) {
+                None => blocked_by.push(blocker),
+                Some(replacement) => {
+                    blocked_by.extend(replacement);
+                    changed = true;
+                }
+            }
+        }
+        task.blocked_by = blocked_by;
+    }
+    changed
+}
+
+/// Writes a list as a Markdown checklist into the `exports` folder.
+pub fn export_list(list_name: &str) -> Result<PathBuf, String> {
+    let tasks = read_tasks(list_name.to_string())?;
+
+    let mut contents = format!("# {}\n\n", list_display_name(list_name));
+    for task in &tasks.tasks {
+        let mark = if task.completed { "x" } else { " " };
+        contents.push_str(&format!("- [{}] {}\n", mark, task.name));
+        if !task.description.is_empty() {
+            contents.push_str(&format!("  {}\n", task.description));
+        }
+        let tags = task.tags.join(", ");
+        if !tags.is_empty() {
+            contents.push_str(&format!("  Tags: {}\n", tags));
+        }
+    }
+
+    let dir = get_path("exports");
+    fs::create_dir_all(&dir).map_err(|err| format!("Failed to create directory: {}", err))?;
+    let mut path = dir;
+    path.push(format!("{}.md", list_name));
+    fs::write(&path, contents).map_err(|err| format!("Failed to write file: {}", err))?;
+    Ok(path)
+}
+
+/// Writes a time report as CSV into the `exports` folder.
+pub fn export_time_report(name: &str, csv: &str) -> Result<PathBuf, String> {
+    let dir = get_path("exports");
+    fs::create_dir_all(&dir).map_err(|err| format!("Failed to create directory: {}", err))?;
+    let mut path = dir;
+    path.push(format!("{}.csv", name));
+    fs::write(&path, csv).map_err(|err| format!("Failed to write file: {}", err))?;
+    Ok(path)
+}
+
+/// Creates an empty list called `name` and returns the file name it was
+/// stored under. Existing files are never overwritten.
+pub fn create_new_task_file(name: String) -> Result<String, String> {
+    let name = validate_list_name(&name, None)?;
+    let file = unique_file_name(&name);
+    let tasks = Tasks {
+        tasks: vec![],
+        meta: ListMeta {
+            name,
+            ..ListMeta::default()
+        },
+    };
+    write_new_list_file(&get_path(&(file.clone() + ".json")), &tasks)?;
+    Ok(file)
+}
+
+/// Deletes a list and every blocked-by reference to its tasks.
+pub fn delete_task_file(name: String) -> Result<(), String> {
+    let path_name = name.clone() + ".json";
     let path = get_path(&path_name);
-    fs::remove_file(&path).expect("Failed to delete file");
+    fs::remove_file(&path).map_err(|err| format!("Failed to delete file: {}", err))?;
+    // A new list with the same name starts with a clean history.
+    let _ = fs::remove_file(history_path(&name));
+    rewrite_dependencies(dropping_list(&name));
+    Ok(())
+}
+
+/// Longest list name accepted, in characters.
+const MAX_LIST_NAME: usize = 64;
+
+/// Checks a display name for a new or renamed list. Names have to be unique
+/// among all lists, archived ones included, `except` skips the list that is
+/// being renamed. Returns the trimmed name.
+pub fn validate_list_name(name: &str, except: Option<&str>) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("The list name cannot be empty".to_string());
+    }
+    if name.chars().count() > MAX_LIST_NAME {
+        return Err(format!(
+            "The list name cannot be longer than {} characters",
+            MAX_LIST_NAME
+        ));
+    }
+    if name.chars().any(char::is_control) {
+        return Err("The list name cannot contain control characters".to_string());
+    }
+
+    let taken = get_lists()
+        .into_iter()
+        .chain(get_archived_lists())
+        .filter(|list| Some(list.file.as_str()) != except)
+        .any(|list| list.name.to_lowercase() == name.to_lowercase());
+    if taken {
+        return Err(format!("A list called \"{}\" already exists", name));
+    }
+    Ok(name.to_string())
+}
+
+/// Turns a display name into a file name that stays inside the data
+/// directory: letters and digits of any script are kept, everything else
+/// becomes a dash.
+pub fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-').to_string();
+    // Device names cannot be used as file names on Windows.
+    const RESERVED: [&str; 6] = ["con", "prn", "aux", "nul", "com1", "lpt1"];
+    if slug.is_empty() {
+        "list".to_string()
+    } else if RESERVED.contains(&slug.as_str()) {
+        slug + "-list"
+    } else {
+        slug
+    }
+}
+
+/// A slug for `name` that no active or archived list uses yet.
+fn unique_file_name(name: &str) -> String {
+    let base = slugify(name);
+    let is_free = |file: &str| {
+        file != "settings"
+            && !get_path(&(file.to_string() + ".json")).exists()
+            && !archive_path(file).exists()
+    };
+
+    let mut file = base.clone();
+    let mut counter = 2;
+    while !is_free(&file) {
+        file = format!("{}-{}", base, counter);
+        counter += 1;
+    }
+    file
+}
+
+fn write_new_list_file(path: &PathBuf, tasks: &Tasks) -> Result<(), String> {
+    create_dir();
+    let json_str = serde_json::to_string_pretty(tasks)
+        .map_err(|err| format!("Failed to serialize tasks: {}", err))?;
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map_err(|err| format!("Failed to create file: {}", err))?;
+    file.write_all(json_str.as_bytes())
+        .map_err(|err| format!("Failed to write file: {}", err))
+}
+
+/// Renames a list. Only the display name changes, the file keeps its name.
+pub fn rename_task_file(file: &str, new_name: &str) -> Result<(), String> {
+    let new_name = validate_list_name(new_name, Some(file))?;
+    let mut meta = read_tasks(file.to_string())?.meta;
+    meta.name = new_name;
+    write_list_meta(file, &meta)
+}
+
+/// Cleans up a folder path such as ` work / clients/ ` into `work/clients`.
+pub fn normalize_folder(folder: &str) -> String {
+    folder
+        .split('/')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Files a list under another folder, an empty path moves it to the top level.
+pub fn move_list_to_folder(file: &str, folder: &str) -> Result<(), String> {
+    let mut meta = read_tasks(file.to_string())?.meta;
+    meta.folder = normalize_folder(folder);
+    write_list_meta(file, &meta)
+}
+
+/// Replaces the metadata stored with a list, leaving its tasks untouched.
+pub fn write_list_meta(file: &str, meta: &ListMeta) -> Result<(), String> {
+    let mut tasks = read_tasks(file.to_string())?;
+    // The id counter belongs to the file, a copy of the metadata held by the
+    // caller may be older.
+    tasks.meta = ListMeta {
+        next_id: tasks.next_id().max(meta.next_id),
+        ..meta.clone()
+    };
+
+    let json_str = serde_json::to_string_pretty(&tasks)
+        .map_err(|err| format!("Failed to serialize tasks: {}", err))?;
+    fs::write(get_path(&(file.to_string() + ".json")), json_str)
+        .map_err(|err| format!("Failed to write file: {}", err))
+}
+
+/// Copies a list under a new name, optionally marking every task as open.
+/// Returns the file name of the copy.
+pub fn duplicate_task_file(
+    file: &str,
+    new_name: &str,
+    reset_completed: bool,
+) -> Result<String, String> {
+    let new_name = validate_list_name(new_name, None)?;
+    let mut tasks = read_tasks(file.to_string())?;
+    if reset_completed {
+        for task in tasks.tasks.iter_mut() {
+            task.completed = false;
+            task.status.clear();
+        }
+    }
+
+    let new_file = unique_file_name(&new_name);
+    // Tasks waiting on their own list keep doing so in the copy.
+    for blocker in tasks
+        .tasks
+        .iter_mut()
+        .flat_map(|task| task.blocked_by.iter_mut())
+        .filter(|blocker| blocker.list == file)
+    {
+        blocker.list = new_file.clone();
+    }
+    tasks.meta.name = new_name;
+    write_new_list_file(&get_path(&(new_file.clone() + ".json")), &tasks)?;
+    Ok(new_file)
+}
+
+fn archive_path(name: &str) -> PathBuf {
+    let mut path = get_path("archive");
+    path.push(name.to_string() + ".json");
+    path
+}
+
+/// Moves a list into the `archive` folder, which keeps it out of the picker.
+pub fn archive_task_file(name: &str) -> Result<(), String> {
+    let archive_dir = get_path("archive");
+    fs::create_dir_all(&archive_dir)
+        .map_err(|err| format!("Failed to create directory: {}", err))?;
+    fs::rename(get_path(&(name.to_string() + ".json")), archive_path(name))
+        .map_err(|err| format!("Failed to archive list: {}", err))
+}
+
+pub fn restore_task_file(name: &str) -> Result<(), String> {
+    let path = get_path(&(name.to_string() + ".json"));
+    if path.exists() {
+        return Err(format!("A list called \"{}\" already exists", name));
+    }
+    fs::rename(archive_path(name), path).map_err(|err| format!("Failed to restore list: {}", err))
+}
+
+pub fn read_archived_tasks(name: &str) -> Result<Tasks, String> {
+    let contents = fs::read_to_string(archive_path(name))
+        .map_err(|err| format!("Failed to read file: {}", err))?;
+    if contents.is_empty() {
+        return Ok(Tasks {
+            tasks: vec![],
+            meta: ListMeta::default(),
+        });
+    }
+    serde_json::from_str(&contents).map_err(|err| format!("Failed to parse JSON: {}", err))
+}
+
+/// A list as offered in pickers: the file it lives in and the name shown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListInfo {
+    pub file: String,
+    pub name: String,
+    pub pinned: Option<u32>,
+    pub folder: String,
+    pub icon: ListIcon,
+    pub color: ListColor,
+    pub open: usize,
+    pub total: usize,
+    pub overdue: usize,
+}
+
+impl ListInfo {
+    pub fn new(file: String, tasks: Option<&Tasks>) -> Self {
+        let meta = tasks.map(|tasks| tasks.meta.clone()).unwrap_or_default();
+        let entries = tasks.map(|tasks| &tasks.tasks[..]).unwrap_or_default();
+        let today = Local::now().date_naive();
+        let name = if meta.name.is_empty() {
+            file.clone()
+        } else {
+            meta.name
+        };
+        Self {
+            file,
+            name,
+            pinned: meta.pinned,
+            folder: meta.folder,
+            icon: meta.icon,
+            color: meta.color,
+            open: entries.iter().filter(|task| !task.completed).count(),
+            total: entries.len(),
+            overdue: entries.iter().filter(|task| task.is_overdue(today)).count(),
+        }
+    }
+}
+
+impl std::fmt::Display for ListInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.pinned.is_some() {
+            write!(f, "* ")?;
+        }
+        if !self.folder.is_empty() {
+            write!(f, "{}/", self.folder)?;
+        }
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Pinned lists first in the order they were pinned, then by name.
+pub fn sort_lists(lists: &mut [ListInfo]) {
+    lists.sort_by_key(|list| (list.pinned.unwrap_or(u32::MAX), list.name.to_lowercase()));
+}
+
+/// All active lists with their display names, lists written before display
+/// names existed show their file name.
+pub fn get_lists() -> Vec<ListInfo> {
+    read_all_lists().into_iter().map(|(list, _)| list).collect()
+}
+
+/// Every active list together with its tasks, in picker order.
+pub fn read_all_lists() -> Vec<(ListInfo, Tasks)> {
+    let mut lists: Vec<(ListInfo, Tasks)> = get_files()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|file| {
+            let tasks = read_tasks(file.clone()).unwrap_or(Tasks {
+                tasks: vec![],
+                meta: ListMeta::default(),
+            });
+            (ListInfo::new(file, Some(&tasks)), tasks)
+        })
+        .collect();
+    lists.sort_by_key(|(list, _)| (list.pinned.unwrap_or(u32::MAX), list.name.to_lowercase()));
+    lists
+}
+
+pub fn get_archived_lists() -> Vec<ListInfo> {
+    let mut lists: Vec<ListInfo> = get_archived_files()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|file| {
+            let tasks = read_archived_tasks(&file).ok();
+            ListInfo::new(file, tasks.as_ref())
+        })
+        .collect();
+    sort_lists(&mut lists);
+    lists
+}
+
+pub fn list_display_name(file: &str) -> String {
+    ListInfo::new(file.to_string(), read_tasks(file.to_string()).ok().as_ref()).name
+}
+
+pub fn get_archived_files() -> Result<Vec<String>, String> {
+    let dir = match fs::read_dir(get_path("archive")) {
+        Ok(dir) => dir,
+        Err(_) => return Ok(Vec::new()),
+    };
+
+    let mut file_names: Vec<String> = dir
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_str()?.to_string();
+            file_name.strip_suffix(".json").map(str::to_string)
+        })
+        .collect();
+    file_names.sort();
+    Ok(file_names)
 }
 
 pub fn get_files() -> Result<Vec<String>, String> {
@@ -218,14 +1098,12 @@ pub fn get_files() -> Result<Vec<String>, String> {
     };
 
     let mut file_names = Vec::new();
-    for entry in dir {
-        if let Ok(entry) = entry {
-            if entry.file_type().map(|ft| ft.is_file()).unwrap_or(false) {
-                if let Some(file_name) = entry.file_name().to_str() {
-                    if file_name != "settings.json" {
-                        let test = file_name.replace(".json", "");
-                        file_names.push(test.to_string());
-                    }
+    for entry in dir.flatten() {
+        if entry.file_type().map(|ft| ft.is_file()).unwrap_or(false) {
+            if let Some(file_name) = entry.file_name().to_str() {
+                if file_name != "settings.json" {
+                    let test = file_name.replace(".json", "");
+                    file_names.push(test.to_string());
                 }
             }
         }
@@ -233,3 +1111,150 @@ pub fn get_files() -> Result<Vec<String>, String> {
 
     Ok(file_names)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gui::Status;
+
+    fn read(json: &str) -> Tasks {
+        let mut tasks: Tasks = serde_json::from_str(json).unwrap();
+        normalize_statuses(&mut tasks);
+        tasks
+    }
+
+    fn statuses(tasks: &Tasks) -> Vec<(String, bool)> {
+        tasks
+            .meta
+            .statuses()
+            .into_iter()
+            .map(|status| (status.name, status.done))
+            .collect()
+    }
+
+    fn task_statuses(tasks: &Tasks) -> Vec<(&str, bool)> {
+        tasks
+            .tasks
+            .iter()
+            .map(|task| (task.status.as_str(), task.completed))
+            .collect()
+    }
+
+    #[test]
+    fn completed_tasks_get_a_status() {
+        let tasks = read(
+            r#"{"tasks": [
+                {"id": 0, "name": "a", "description": "", "tags": [], "completed": true},
+                {"id": 1, "name": "b", "description": "", "tags": [], "completed": false}
+            ]}"#,
+        );
+        assert_eq!(task_statuses(&tasks), [("Done", true), ("Todo", false)]);
+    }
+
+    #[test]
+    fn legacy_columns_are_read_as_statuses() {
+        let tasks = read(
+            r#"{"tasks": [
+                {"id": 0, "name": "a", "description": "", "tags": [], "completed": false,
+                 "status": "Working"},
+                {"id": 1, "name": "b", "description": "", "tags": [], "completed": true}
+            ], "meta": {"columns": ["Inbox", "Working", "Shipped"]}}"#,
+        );
+        assert_eq!(
+            statuses(&tasks),
+            [
+                ("Inbox".to_string(), false),
+                ("Working".to_string(), false),
+                ("Shipped".to_string(), true)
+            ]
+        );
+        assert_eq!(
+            task_statuses(&tasks),
+            [("Working", false), ("Shipped", true)]
+        );
+    }
+
+    #[test]
+    fn removed_statuses_fall_back_to_the_first_open_or_done_one() {
+        let tasks = read(
+            r#"{"tasks": [
+                {"id": 0, "name": "a", "description": "", "tags": [], "completed": false,
+                 "status": "Review"},
+                {"id": 1, "name": "b", "description": "", "tags": [], "completed": true,
+                 "status": "Review"},
+                {"id": 2, "name": "c", "description": "", "tags": [], "completed": true,
+                 "status": "Next"}
+            ], "meta": {"statuses": [
+                {"name": "Next"}, {"name": "Closed", "done": true}, {"name": "Later"}
+            ]}}"#,
+        );
+        assert_eq!(
+            task_statuses(&tasks),
+            [("Next", false), ("Closed", true), ("Closed", true)]
+        );
+    }
+
+    #[test]
+    fn deleting_a_list_drops_references_to_its_tasks() {
+        let mut tasks = read(
+            r#"{"tasks": [
+                {"id": 0, "name": "a", "description": "", "tags": [], "completed": false,
+                 "blocked_by": [{"list": "gone", "id": 3}, {"list": "kept", "id": 3}]},
+                {"id": 1, "name": "b", "description": "", "tags": [], "completed": false,
+                 "blocked_by": [{"list": "gone", "id": 7}]}
+            ]}"#,
+        );
+        assert!(rewrite_blockers(&mut tasks, &dropping_list("gone")));
+        assert_eq!(
+            tasks.tasks[0].blocked_by,
+            [TaskRef {
+                list: "kept".to_string(),
+                id: 3
+            }]
+        );
+        assert!(tasks.tasks[1].blocked_by.is_empty());
+        assert!(!rewrite_blockers(&mut tasks, &dropping_list("gone")));
+    }
+
+    #[test]
+    fn copies_leave_the_reminder_and_running_timer_to_the_original() {
+        let tasks = read(
+            r#"{"tasks": [
+                {"id": 0, "name": "a", "description": "", "tags": [], "completed": false,
+                 "remind_at": "2026-10-20T09:00:00+00:00",
+                 "time_sessions": [
+                    {"start": "2026-10-19T08:00:00+00:00", "end": "2026-10-19T09:00:00+00:00"},
+                    {"start": "2026-10-19T10:00:00+00:00", "end": null}
+                 ]}
+            ]}"#,
+        );
+        let original = &tasks.tasks[0];
+        let copy = separate_copy(original);
+        assert_eq!(copy.remind_at, None);
+        assert_eq!(copy.time_sessions, original.time_sessions[..1]);
+        assert_eq!(original.time_sessions.len(), 2);
+        assert!(original.remind_at.is_some());
+    }
+
+    #[test]
+    fn statuses_survive_a_round_trip() {
+        let tasks = read(
+            r#"{"tasks": [], "meta": {"statuses": ["Todo", {"name": "Done", "done": true}]}}"#,
+        );
+        let written = serde_json::to_string(&tasks).unwrap();
+        let reread = read(&written);
+        assert_eq!(
+            reread.meta.statuses,
+            [
+                Status {
+                    name: "Todo".to_string(),
+                    done: false
+                },
+                Status {
+                    name: "Done".to_string(),
+                    done: true
+                }
+            ]
+        );
+    }
+}
@@ -0,0 +1,183 @@
+use iced::keyboard::{KeyCode, Modifiers};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Something a keyboard shortcut can trigger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Action {
+    NewTask,
+    EditTask,
+    ToggleComplete,
+    DeleteTask,
+    FocusSearch,
+    SwitchList,
+    NextTask,
+    PreviousTask,
+    MoveTaskUp,
+    MoveTaskDown,
+    SelectAll,
+    CommandPalette,
+}
+
+impl Action {
+    pub const ALL: [Action; 12] = [
+        Action::NewTask,
+        Action::EditTask,
+        Action::ToggleComplete,
+        Action::DeleteTask,
+        Action::FocusSearch,
+        Action::SwitchList,
+        Action::NextTask,
+        Action::PreviousTask,
+        Action::MoveTaskUp,
+        Action::MoveTaskDown,
+        Action::SelectAll,
+        Action::CommandPalette,
+    ];
+
+    /// The chords used when the user has not bound the action themselves.
+    pub fn default_chords(&self) -> Vec<String> {
+        let chords: &[&str] = match self {
+            Action::NewTask => &["N"],
+            Action::EditTask => &["E"],
+            Action::ToggleComplete => &["Space"],
+            Action::DeleteTask => &["Delete"],
+            Action::FocusSearch => &["/"],
+            Action::SwitchList => &["Ctrl+L"],
+            Action::NextTask => &["Down", "J"],
+            Action::PreviousTask => &["Up", "K"],
+            Action::MoveTaskUp => &["Alt+Up"],
+            Action::MoveTaskDown => &["Alt+Down"],
+            Action::SelectAll => &["Ctrl+A"],
+            Action::CommandPalette => &["Ctrl+P"],
+        };
+        chords.iter().map(|chord| chord.to_string()).collect()
+    }
+}
+
+impl std::fmt::Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::NewTask => write!(f, "New task"),
+            Action::EditTask => write!(f, "Edit task"),
+            Action::ToggleComplete => write!(f, "Toggle complete"),
+            Action::DeleteTask => write!(f, "Delete task"),
+            Action::FocusSearch => write!(f, "Focus search"),
+            Action::SwitchList => write!(f, "Switch list"),
+            Action::NextTask => write!(f, "Next task"),
+            Action::PreviousTask => write!(f, "Previous task"),
+            Action::MoveTaskUp => write!(f, "Move task up"),
+            Action::MoveTaskDown => write!(f, "Move task down"),
+            Action::SelectAll => write!(f, "Select all"),
+            Action::CommandPalette => write!(f, "Command palette"),
+        }
+    }
+}
+
+/// User bindings, keyed by action. Actions missing from the map keep their
+/// default chords so older settings files pick up new shortcuts.
+pub type Keymap = BTreeMap<Action, Vec<String>>;
+
+pub fn default_keymap() -> Keymap {
+    Action::ALL
+        .iter()
+        .map(|action| (*action, action.default_chords()))
+        .collect()
+}
+
+pub fn chords_for(keymap: &Keymap, action: Action) -> Vec<String> {
+    keymap
+        .get(&action)
+        .cloned()
+        .unwrap_or_else(|| action.default_chords())
+}
+
+/// Finds the action bound to a key press, if any.
+pub fn action_for(keymap: &Keymap, key_code: KeyCode, modifiers: Modifiers) -> Option<Action> {
+    Action::ALL.into_iter().find(|action| {
+        chords_for(keymap, *action)
+            .iter()
+            .filter_map(|chord| parse_chord(chord))
+            .any(|(code, mods)| code == key_code && mods == modifiers)
+    })
+}
+
+/// Parses chords such as `Ctrl+Shift+K`, `Space` or `/`.
+pub fn parse_chord(chord: &str) -> Option<(KeyCode, Modifiers)> {
+    let mut modifiers = Modifiers::empty();
+    let mut key_code = None;
+
+    for part in chord.split('+').map(str::trim) {
+        match part.to_lowercase().as_str() {
+            "ctrl" | "control" => modifiers |= Modifiers::CTRL,
+            "shift" => modifiers |= Modifiers::SHIFT,
+            "alt" => modifiers |= Modifiers::ALT,
+            "super" | "cmd" | "logo" => modifiers |= Modifiers::LOGO,
+            _ => key_code = Some(parse_key(part)?),
+        }
+    }
+
+    key_code.map(|code| (code, modifiers))
+}
+
+fn parse_key(key: &str) -> Option<KeyCode> {
+    let code = match key.to_lowercase().as_str() {
+        "a" => KeyCode::A,
+        "b" => KeyCode::B,
+        "c" => KeyCode::C,
+        "d" => KeyCode::D,
+        "e" => KeyCode::E,
+        "f" => KeyCode::F,
+        "g" => KeyCode::G,
+        "h" => KeyCode::H,
+        "i" => KeyCode::I,
+        "j" => KeyCode::J,
+        "k" => KeyCode::K,
+        "l" => KeyCode::L,
+        "m" => KeyCode::M,
+        "n" => KeyCode::N,
+        "o" => KeyCode::O,
+        "p" => KeyCode::P,
+        "q" => KeyCode::Q,
+        "r" => KeyCode::R,
+        "s" => KeyCode::S,
+        "t" => KeyCode::T,
+        "u" => KeyCode::U,
+        "v" => KeyCode::V,
+        "w" => KeyCode::W,
+        "x" => KeyCode::X,
+        "y" => KeyCode::Y,
+        "z" => KeyCode::Z,
+        "0" => KeyCode::Key0,
+        "1" => KeyCode::Key1,
+        "2" => KeyCode::Key2,
+        "3" => KeyCode::Key3,
+        "4" => KeyCode::Key4,
+        "5" => KeyCode::Key5,
+        "6" => KeyCode::Key6,
+        "7" => KeyCode::Key7,
+        "8" => KeyCode::Key8,
+        "9" => KeyCode::Key9,
+        "space" => KeyCode::Space,
+        "delete" | "del" => KeyCode::Delete,
+        "backspace" => KeyCode::Backspace,
+        "enter" | "return" => KeyCode::Enter,
+        "escape" | "esc" => KeyCode::Escape,
+        "tab" => KeyCode::Tab,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "/" | "slash" => KeyCode::Slash,
+        "," | "comma" => KeyCode::Comma,
+        "." | "period" => KeyCode::Period,
+        "-" | "minus" => KeyCode::Minus,
+        "=" | "equals" => KeyCode::Equals,
+        _ => return None,
+    };
+    Some(code)
+}
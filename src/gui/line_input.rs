@@ -0,0 +1,178 @@
+use iced::advanced::layout::{self, Layout};
+use iced::advanced::widget::tree::{self, Tree};
+use iced::advanced::widget::{Operation, Widget};
+use iced::advanced::{renderer, Clipboard, Shell};
+use iced::keyboard::{self, KeyCode};
+use iced::widget::text_input::{self, cursor, TextInput, Value};
+use iced::{event, mouse, Element, Event, Length, Rectangle, Renderer};
+
+/// One line of a multi-line editor. A text input only knows a single line,
+/// this wraps one and reports the keys that cross line boundaries: Enter
+/// splits the line at the cursor, Backspace at its start joins it with the
+/// line above, Delete at its end joins the line below into it.
+pub struct LineInput<'a, Message> {
+    input: TextInput<'a, Message>,
+    value: Value,
+    on_split: Box<dyn Fn(usize, usize) -> Message + 'a>,
+    on_join_previous: Option<Message>,
+    on_join_next: Option<Message>,
+}
+
+impl<'a, Message: Clone> LineInput<'a, Message> {
+    /// `on_split` gets the byte range of the selection, or the cursor as an
+    /// empty range, which is dropped when the line is split.
+    pub fn new(
+        input: TextInput<'a, Message>,
+        value: &str,
+        on_split: impl Fn(usize, usize) -> Message + 'a,
+    ) -> Self {
+        LineInput {
+            input,
+            value: Value::new(value),
+            on_split: Box::new(on_split),
+            on_join_previous: None,
+            on_join_next: None,
+        }
+    }
+
+    pub fn on_join_previous(mut self, message: Message) -> Self {
+        self.on_join_previous = Some(message);
+        self
+    }
+
+    pub fn on_join_next(mut self, message: Message) -> Self {
+        self.on_join_next = Some(message);
+        self
+    }
+
+    /// The byte offset of a cursor position, which counts graphemes.
+    fn byte_offset(&self, index: usize) -> usize {
+        self.value.until(index).to_string().len()
+    }
+}
+
+impl<'a, Message: Clone> Widget<Message, Renderer> for LineInput<'a, Message> {
+    fn tag(&self) -> tree::Tag {
+        Widget::<Message, Renderer>::tag(&self.input)
+    }
+
+    fn state(&self) -> tree::State {
+        Widget::<Message, Renderer>::state(&self.input)
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        Widget::<Message, Renderer>::diff(&self.input, tree)
+    }
+
+    fn width(&self) -> Length {
+        Widget::<Message, Renderer>::width(&self.input)
+    }
+
+    fn height(&self) -> Length {
+        Widget::<Message, Renderer>::height(&self.input)
+    }
+
+    fn layout(&self, renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        Widget::<Message, Renderer>::layout(&self.input, renderer, limits)
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn Operation<Message>,
+    ) {
+        Widget::<Message, Renderer>::operate(&self.input, tree, layout, renderer, operation)
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) -> event::Status {
+        if let Event::Keyboard(keyboard::Event::KeyPressed { key_code, .. }) = event {
+            let state = tree.state.downcast_ref::<text_input::State>();
+            if state.is_focused() {
+                let position = state.cursor().state(&self.value);
+                let message = match (key_code, position) {
+                    (KeyCode::Enter | KeyCode::NumpadEnter, cursor::State::Index(index)) => {
+                        let offset = self.byte_offset(index);
+                        Some((self.on_split)(offset, offset))
+                    }
+                    (
+                        KeyCode::Enter | KeyCode::NumpadEnter,
+                        cursor::State::Selection { start, end },
+                    ) => Some((self.on_split)(
+                        self.byte_offset(start.min(end)),
+                        self.byte_offset(start.max(end)),
+                    )),
+                    (KeyCode::Backspace, cursor::State::Index(0)) => self.on_join_previous.clone(),
+                    (KeyCode::Delete, cursor::State::Index(index)) if index == self.value.len() => {
+                        self.on_join_next.clone()
+                    }
+                    _ => None,
+                };
+                if let Some(message) = message {
+                    shell.publish(message);
+                    return event::Status::Captured;
+                }
+            }
+        }
+        self.input.on_event(
+            tree, event, layout, cursor, renderer, clipboard, shell, viewport,
+        )
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &iced::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        Widget::<Message, Renderer>::draw(
+            &self.input,
+            tree,
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor,
+            viewport,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        Widget::<Message, Renderer>::mouse_interaction(
+            &self.input,
+            tree,
+            layout,
+            cursor,
+            viewport,
+            renderer,
+        )
+    }
+}
+
+impl<'a, Message: Clone + 'a> From<LineInput<'a, Message>> for Element<'a, Message> {
+    fn from(input: LineInput<'a, Message>) -> Self {
+        Element::new(input)
+    }
+}
@@ -0,0 +1,332 @@
+use crate::gui::{ui_theme, Message};
+use iced::widget::{container, horizontal_rule, mouse_area, row, text, Column, Text};
+use iced::{font, Color, Element, Font, Length};
+use iced_aw::Wrap;
+use pulldown_cmark::{Event, Parser, Tag};
+
+/// A run of text with one style.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Span {
+    pub text: String,
+    pub bold: bool,
+    pub italic: bool,
+    pub code: bool,
+    pub link: Option<String>,
+}
+
+/// The parts of Markdown task descriptions are shown with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Block {
+    Heading(usize, Vec<Span>),
+    Paragraph(Vec<Span>),
+    /// An entry of a list nested `depth` levels deep, `marker` is a bullet
+    /// or the number in ordered lists.
+    Item {
+        depth: usize,
+        marker: String,
+        spans: Vec<Span>,
+    },
+    Code(String),
+    Rule,
+}
+
+/// Collects the events of the parser into blocks.
+#[derive(Default)]
+struct Builder {
+    blocks: Vec<Block>,
+    spans: Vec<Span>,
+    bold: usize,
+    italic: usize,
+    link: Option<String>,
+    heading: Option<usize>,
+    /// The next number of each open list, `None` for bullet lists.
+    lists: Vec<Option<u64>>,
+    item: Option<(usize, String)>,
+    code: Option<String>,
+}
+
+impl Builder {
+    fn push_text(&mut self, text: &str, code: bool) {
+        if let Some(block) = &mut self.code {
+            block.push_str(text);
+            return;
+        }
+        self.spans.push(Span {
+            text: text.to_string(),
+            bold: self.bold > 0,
+            italic: self.italic > 0,
+            code,
+            link: self.link.clone(),
+        });
+    }
+
+    /// Ends the block the collected spans belong to.
+    fn flush(&mut self) {
+        if self.spans.is_empty() {
+            return;
+        }
+        let spans = std::mem::take(&mut self.spans);
+        let block = if let Some(level) = self.heading.take() {
+            Block::Heading(level, spans)
+        } else if let Some((depth, marker)) = self.item.take() {
+            Block::Item {
+                depth,
+                marker,
+                spans,
+            }
+        } else {
+            Block::Paragraph(spans)
+        };
+        self.blocks.push(block);
+    }
+}
+
+pub fn parse(input: &str) -> Vec<Block> {
+    let mut builder = Builder::default();
+    for event in Parser::new(input) {
+        match event {
+            Event::Start(Tag::Heading(level, _, _)) => {
+                builder.flush();
+                builder.heading = Some(level as usize);
+            }
+            Event::Start(Tag::List(start)) => {
+                builder.flush();
+                builder.lists.push(start);
+            }
+            Event::End(Tag::List(_)) => {
+                builder.lists.pop();
+            }
+            Event::Start(Tag::Item) => {
+                builder.flush();
+                let marker = match builder.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}.", *number - 1)
+                    }
+                    _ => "•".to_string(),
+                };
+                builder.item = Some((builder.lists.len().saturating_sub(1), marker));
+            }
+            Event::Start(Tag::CodeBlock(_)) => {
+                builder.flush();
+                builder.code = Some(String::new());
+            }
+            Event::End(Tag::CodeBlock(_)) => {
+                if let Some(code) = builder.code.take() {
+                    builder
+                        .blocks
+                        .push(Block::Code(code.trim_end_matches('\n').to_string()));
+                }
+            }
+            Event::Start(Tag::Strong) => builder.bold += 1,
+            Event::End(Tag::Strong) => builder.bold = builder.bold.saturating_sub(1),
+            Event::Start(Tag::Emphasis) => builder.italic += 1,
+            Event::End(Tag::Emphasis) => builder.italic = builder.italic.saturating_sub(1),
+            Event::Start(Tag::Link(_, url, _)) => builder.link = Some(url.to_string()),
+            Event::End(Tag::Link(..)) => builder.link = None,
+            Event::End(Tag::Paragraph | Tag::Heading(..) | Tag::Item) => builder.flush(),
+            Event::Text(text) => builder.push_text(&text, false),
+            Event::Code(text) => builder.push_text(&text, true),
+            Event::Html(text) => builder.push_text(&text, false),
+            Event::SoftBreak | Event::HardBreak => builder.push_text(" ", false),
+            Event::Rule => {
+                builder.flush();
+                builder.blocks.push(Block::Rule);
+            }
+            _ => {}
+        }
+    }
+    builder.flush();
+    builder.blocks
+}
+
+/// Schemes of links that are handed to the system to open. Anything else,
+/// like `file:` or custom URL handlers, could run programs on a click.
+const OPENABLE_SCHEMES: [&str; 3] = ["http", "https", "mailto"];
+
+/// Whether a link in a description may be opened.
+pub fn is_openable(url: &str) -> bool {
+    url.trim().split_once(':').is_some_and(|(scheme, _)| {
+        OPENABLE_SCHEMES
+            .iter()
+            .any(|openable| scheme.eq_ignore_ascii_case(openable))
+    })
+}
+
+/// The first line of a description without Markdown syntax, shown while the
+/// details are folded away.
+pub fn summary(input: &str) -> String {
+    parse(input)
+        .first()
+        .map(|block| match block {
+            Block::Heading(_, spans) | Block::Paragraph(spans) | Block::Item { spans, .. } => {
+                spans.iter().map(|span| span.text.as_str()).collect()
+            }
+            Block::Code(code) => code.lines().next().unwrap_or_default().to_string(),
+            Block::Rule => String::new(),
+        })
+        .unwrap_or_default()
+}
+
+/// Lays out parsed Markdown. iced has no italic fonts, emphasis is shown in
+/// the accent color instead.
+pub fn view<'a>(blocks: &[Block], accent: Color, size: u16) -> Column<'a, Message> {
+    let mut content = Column::new().spacing(6).width(Length::Fill);
+    for block in blocks {
+        let element: Element<'a, Message> = match block {
+            Block::Heading(level, spans) => {
+                let size = match level {
+                    1 => size + 8,
+                    2 => size + 4,
+                    _ => size + 2,
+                };
+                inline(spans, accent, size, true)
+            }
+            Block::Paragraph(spans) => inline(spans, accent, size, false),
+            Block::Item {
+                depth,
+                marker,
+                spans,
+            } => row![
+                text(marker).size(size).width(20),
+                inline(spans, accent, size, false)
+            ]
+            .padding([0, 0, 0, *depth as u16 * 20])
+            .into(),
+            Block::Code(code) => container(text(code).font(Font::MONOSPACE).size(size))
+                .style(ui_theme::container_theme())
+                .padding(6)
+                .width(Length::Fill)
+                .into(),
+            Block::Rule => horizontal_rule(1).into(),
+        };
+        content = content.push(element);
+    }
+    content
+}
+
+/// Words of the spans wrapped onto as many lines as needed. Links open in
+/// the browser when clicked.
+fn inline<'a>(spans: &[Span], accent: Color, size: u16, heading: bool) -> Element<'a, Message> {
+    let mut words = Wrap::new().spacing(4.0).line_spacing(2.0);
+    for span in spans {
+        let font = Font {
+            weight: if span.bold || heading {
+                font::Weight::Bold
+            } else {
+                font::Weight::Normal
+            },
+            ..if span.code {
+                Font::MONOSPACE
+            } else {
+                Font::DEFAULT
+            }
+        };
+        for word in span.text.split_whitespace() {
+            let mut word: Text<'a> = text(word).font(font).size(size);
+            if span.italic || span.link.is_some() {
+                word = word.style(accent);
+            }
+            words = match &span.link {
+                Some(url) => {
+                    words.push(mouse_area(word).on_press(Message::LinkClicked(url.clone())))
+                }
+                None => words.push(word),
+            };
+        }
+    }
+    words.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(text: &str) -> Span {
+        Span {
+            text: text.to_string(),
+            ..Span::default()
+        }
+    }
+
+    #[test]
+    fn parse_keeps_inline_styles_apart() {
+        assert_eq!(
+            parse("Buy **milk** and *eggs*, see [the list](https://example.com) or `notes`"),
+            [Block::Paragraph(vec![
+                plain("Buy "),
+                Span {
+                    bold: true,
+                    ..plain("milk")
+                },
+                plain(" and "),
+                Span {
+                    italic: true,
+                    ..plain("eggs")
+                },
+                plain(", see "),
+                Span {
+                    link: Some("https://example.com".to_string()),
+                    ..plain("the list")
+                },
+                plain(" or "),
+                Span {
+                    code: true,
+                    ..plain("notes")
+                },
+            ])]
+        );
+    }
+
+    #[test]
+    fn parse_reads_blocks() {
+        assert_eq!(
+            parse("## Plan\nfirst\nsecond\n\n---\n\n```\nlet x = 1;\n```"),
+            [
+                Block::Heading(2, vec![plain("Plan")]),
+                Block::Paragraph(vec![plain("first"), plain(" "), plain("second")]),
+                Block::Rule,
+                Block::Code("let x = 1;".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_numbers_and_nests_list_items() {
+        let item = |depth, marker: &str, text| Block::Item {
+            depth,
+            marker: marker.to_string(),
+            spans: vec![plain(text)],
+        };
+        assert_eq!(
+            parse("3. one\n4. two\n   - inner\n"),
+            [
+                item(0, "3.", "one"),
+                item(0, "4.", "two"),
+                item(1, "•", "inner")
+            ]
+        );
+    }
+
+    #[test]
+    fn summary_is_the_first_block_as_plain_text() {
+        assert_eq!(summary("# **Big** plan\n\nmore"), "Big plan");
+        assert_eq!(summary("```\ncode\nmore\n```"), "code");
+        assert_eq!(summary(""), "");
+    }
+
+    #[test]
+    fn only_web_and_mail_links_are_openable() {
+        for url in ["https://example.com", "HTTP://example.com", " mailto:a@b.c"] {
+            assert!(is_openable(url), "{:?} was refused", url);
+        }
+        for url in [
+            "file:///etc/passwd",
+            "javascript:alert(1)",
+            "example.com",
+            "",
+        ] {
+            assert!(!is_openable(url), "{:?} was allowed", url);
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use crate::gui::TaskRef;
+use iced::futures::channel::oneshot;
+use std::sync::{Arc, Mutex};
+
+/// How far "Snooze" puts a reminder off.
+pub const SNOOZE_MINUTES: i64 = 10;
+
+/// How often the scheduler looks for reminders that came due.
+pub const CHECK_INTERVAL_SECONDS: u64 = 20;
+
+/// What was picked on a reminder, in the notification or in the app.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReminderAction {
+    Snooze,
+    Done,
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+type Handle = notify_rust::NotificationHandle;
+/// Notifications without actions are not kept around.
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+#[derive(Debug)]
+struct Handle;
+
+/// Notifications that are still on screen, so a reminder answered in the app
+/// can take its notification down.
+#[derive(Debug, Clone, Default)]
+pub struct Shown(Arc<Mutex<Vec<(TaskRef, Handle)>>>);
+
+impl Shown {
+    #[cfg_attr(not(all(unix, not(target_os = "macos"))), allow(dead_code))]
+    fn insert(&self, task: TaskRef, handle: Handle) {
+        if let Ok(mut shown) = self.0.lock() {
+            shown.push((task, handle));
+        }
+    }
+
+    /// Takes the handle of the first notification of `task` that `matches`.
+    fn take(&self, task: &TaskRef, matches: impl Fn(&Handle) -> bool) -> Option<Handle> {
+        let mut shown = self.0.lock().ok()?;
+        let index = shown
+            .iter()
+            .position(|(other, handle)| other == task && matches(handle))?;
+        Some(shown.remove(index).1)
+    }
+
+    /// Closes the notification of a reminder, if it is still shown.
+    pub fn close(&self, task: &TaskRef) {
+        while let Some(handle) = self.take(task, |_| true) {
+            close(handle);
+        }
+    }
+}
+
+/// Shows a desktop notification and waits for one of its actions without
+/// blocking the UI. Resolves to `None` once the notification is dismissed or
+/// closed through `shown`, or when it could not be shown.
+pub async fn notify(
+    summary: String,
+    body: String,
+    task: TaskRef,
+    shown: Shown,
+) -> Option<ReminderAction> {
+    let (sender, receiver) = oneshot::channel();
+    // Waiting for an action blocks on the notification server.
+    std::thread::spawn(move || {
+        let _ = sender.send(show(&summary, &body, &task, &shown));
+    });
+    receiver.await.ok().flatten()
+}
+
+/// Freedesktop notifications over D-Bus, which support actions. They stay
+/// up until they are answered, dismissed or closed from the app, which ends
+/// the wait.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn show(summary: &str, body: &str, task: &TaskRef, shown: &Shown) -> Option<ReminderAction> {
+    use notify_rust::ActionResponse;
+
+    let handle = notify_rust::Notification::new()
+        .appname("Tasks")
+        .summary(summary)
+        .body(body)
+        .action("snooze", &format!("Snooze {} min", SNOOZE_MINUTES))
+        .action("done", "Mark done")
+        .timeout(notify_rust::Timeout::Never)
+        .show()
+        .map_err(|err| println!("Failed to show notification: {}", err))
+        .ok()?;
+    let id = handle.id();
+    // A reminder that went off again replaces its earlier notification.
+    shown.close(task);
+    shown.insert(task.clone(), handle);
+
+    let mut picked = None;
+    let waited = notify_rust::handle_action(id, |action| {
+        picked = match action {
+            ActionResponse::Custom("snooze") => Some(ReminderAction::Snooze),
+            ActionResponse::Custom("done") => Some(ReminderAction::Done),
+            _ => None,
+        }
+    });
+    if let Err(err) = waited {
+        println!("Failed to wait for the notification: {}", err);
+    }
+    shown.take(task, |handle| handle.id() == id);
+    picked
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn close(handle: Handle) {
+    handle.close();
+}
+
+/// Elsewhere notifications have no actions, the reminder bar in the app
+/// offers them instead.
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+fn show(summary: &str, body: &str, _task: &TaskRef, _shown: &Shown) -> Option<ReminderAction> {
+    if let Err(err) = notify_rust::Notification::new()
+        .appname("Tasks")
+        .summary(summary)
+        .body(body)
+        .show()
+    {
+        println!("Failed to show notification: {}", err);
+    }
+    None
+}
+
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+fn close(_handle: Handle) {}
@@ -0,0 +1,258 @@
+use crate::gui::{split_tags, ListedTask};
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// One stretch of work on a task, `end` is `None` while the timer runs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeSession {
+    pub start: DateTime<Local>,
+    pub end: Option<DateTime<Local>>,
+}
+
+impl TimeSession {
+    /// The part of the session that falls between `from` and `to`, a running
+    /// session counts up to `now`.
+    fn within(&self, from: DateTime<Local>, to: DateTime<Local>, now: DateTime<Local>) -> Duration {
+        let start = self.start.max(from);
+        let end = self.end.unwrap_or(now).min(to);
+        (end - start).max(Duration::zero())
+    }
+}
+
+/// All time tracked in `sessions`.
+pub fn tracked(sessions: &[TimeSession], now: DateTime<Local>) -> Duration {
+    sessions
+        .iter()
+        .map(|session| session.end.unwrap_or(now) - session.start)
+        .fold(Duration::zero(), |total, duration| total + duration)
+}
+
+pub fn is_running(sessions: &[TimeSession]) -> bool {
+    sessions.iter().any(|session| session.end.is_none())
+}
+
+/// Closes the running session, if any.
+pub fn stop(sessions: &mut [TimeSession], now: DateTime<Local>) {
+    for session in sessions.iter_mut().filter(|session| session.end.is_none()) {
+        session.end = Some(now);
+    }
+}
+
+/// `1h 05m` or `12m`, for rows and reports.
+pub fn format_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes();
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+/// `1:05:09`, for the running timer in the title.
+pub fn format_clock(duration: Duration) -> String {
+    let seconds = duration.num_seconds().max(0);
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// What the rows of the time report add up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportGroup {
+    Task,
+    Tag,
+    List,
+}
+
+impl ReportGroup {
+    pub const ALL: [ReportGroup; 3] = [ReportGroup::Task, ReportGroup::Tag, ReportGroup::List];
+}
+
+impl std::fmt::Display for ReportGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReportGroup::Task => write!(f, "Per task"),
+            ReportGroup::Tag => write!(f, "Per tag"),
+            ReportGroup::List => write!(f, "Per list"),
+        }
+    }
+}
+
+/// Time tracked from the start of `from` to the end of `to`, summed per
+/// group and sorted by the most time first. Tasks with several tags count
+/// towards each of them, untagged tasks are grouped as "No tag".
+pub fn report(
+    tasks: &[ListedTask],
+    group: ReportGroup,
+    from: NaiveDate,
+    to: NaiveDate,
+    now: DateTime<Local>,
+) -> Vec<(String, Duration)> {
+    let start_of = |day: NaiveDate| {
+        Local
+            .from_local_datetime(&day.and_hms_opt(0, 0, 0).unwrap_or_default())
+            .earliest()
+            .unwrap_or(now)
+    };
+    let (from, to) = (start_of(from), start_of(to + Duration::days(1)));
+
+    let mut totals: BTreeMap<String, Duration> = BTreeMap::new();
+    for task in tasks {
+        let time = task
+            .entry
+            .time_sessions
+            .iter()
+            .map(|session| session.within(from, to, now))
+            .fold(Duration::zero(), |total, duration| total + duration);
+        if time.is_zero() {
+            continue;
+        }
+
+        let keys = match group {
+            ReportGroup::Task => vec![format!("{} ({})", task.entry.name, task.list_name)],
+            ReportGroup::List => vec![task.list_name.clone()],
+            ReportGroup::Tag => {
+                let tags = split_tags(&task.entry.tags);
+                if tags.is_empty() {
+                    vec!["No tag".to_string()]
+                } else {
+                    tags
+                }
+            }
+        };
+        for key in keys {
+            let total = totals.entry(key).or_insert_with(Duration::zero);
+            *total = *total + time;
+        }
+    }
+
+    let mut rows: Vec<(String, Duration)> = totals.into_iter().collect();
+    rows.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    rows
+}
+
+/// The report as CSV with the minutes per row.
+pub fn report_csv(group: ReportGroup, rows: &[(String, Duration)]) -> String {
+    let heading = match group {
+        ReportGroup::Task => "task",
+        ReportGroup::Tag => "tag",
+        ReportGroup::List => "list",
+    };
+    let mut csv = format!("{},minutes\n", heading);
+    for (name, duration) in rows {
+        // Quotes are doubled inside quoted fields.
+        csv.push_str(&format!(
+            "\"{}\",{}\n",
+            name.replace('"', "\"\""),
+            duration.num_minutes()
+        ));
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gui::TaskEntry;
+
+    fn at(day: u32, hour: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, day, hour, 0, 0).unwrap()
+    }
+
+    fn day(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    fn task(
+        list: &str,
+        name: &str,
+        tags: &str,
+        sessions: &[(DateTime<Local>, Option<DateTime<Local>>)],
+    ) -> ListedTask {
+        let mut entry: TaskEntry = serde_json::from_value(serde_json::json!({
+            "id": 0, "name": name, "description": "", "tags": [tags], "completed": false
+        }))
+        .unwrap();
+        entry.time_sessions = sessions
+            .iter()
+            .map(|(start, end)| TimeSession {
+                start: *start,
+                end: *end,
+            })
+            .collect();
+        ListedTask {
+            list: list.to_lowercase(),
+            list_name: list.to_string(),
+            entry,
+        }
+    }
+
+    fn hours(rows: &[(String, Duration)]) -> Vec<(&str, i64)> {
+        rows.iter()
+            .map(|(name, duration)| (name.as_str(), duration.num_hours()))
+            .collect()
+    }
+
+    #[test]
+    fn report_sums_per_group() {
+        let tasks = [
+            task(
+                "Work",
+                "Write",
+                "docs, review",
+                &[(at(13, 9), Some(at(13, 12)))],
+            ),
+            task("Work", "Plan", "", &[(at(14, 9), Some(at(14, 10)))]),
+            task("Home", "Paint", "review", &[(at(14, 9), Some(at(14, 13)))]),
+        ];
+        let report = |group| report(&tasks, group, day(12), day(16), at(16, 20));
+        assert_eq!(
+            hours(&report(ReportGroup::Task)),
+            [("Paint (Home)", 4), ("Write (Work)", 3), ("Plan (Work)", 1)]
+        );
+        assert_eq!(
+            hours(&report(ReportGroup::List)),
+            [("Home", 4), ("Work", 4)]
+        );
+        assert_eq!(
+            hours(&report(ReportGroup::Tag)),
+            [("review", 7), ("docs", 3), ("No tag", 1)]
+        );
+    }
+
+    #[test]
+    fn report_only_counts_time_within_the_days() {
+        let tasks = [
+            task("Work", "Late", "", &[(at(11, 22), Some(at(12, 2)))]),
+            task("Work", "Before", "", &[(at(10, 9), Some(at(10, 17)))]),
+            task("Work", "Running", "", &[(at(16, 18), None)]),
+        ];
+        assert_eq!(
+            hours(&report(
+                &tasks,
+                ReportGroup::Task,
+                day(12),
+                day(16),
+                at(17, 3)
+            )),
+            [("Running (Work)", 6), ("Late (Work)", 2)]
+        );
+    }
+
+    #[test]
+    fn report_csv_quotes_names() {
+        let rows = [
+            ("Say \"hi\", then go".to_string(), Duration::minutes(90)),
+            ("Plain".to_string(), Duration::seconds(59)),
+        ];
+        assert_eq!(
+            report_csv(ReportGroup::Task, &rows),
+            "task,minutes\n\"Say \"\"hi\"\", then go\",90\n\"Plain\",0\n"
+        );
+        assert_eq!(report_csv(ReportGroup::Tag, &[]), "tag,minutes\n");
+    }
+}
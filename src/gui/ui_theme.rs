@@ -1,13 +1,16 @@
 use iced::theme::Button as ThemeButton;
 use iced::theme::Checkbox as ThemeCheckbox;
 use iced::theme::Container as ThemeContainer;
-use iced::theme::Menu as ThemeMenu;
+use iced::theme::Palette as IcedPalette;
 use iced::theme::PickList as ThemePickList;
 use iced::theme::Scrollable as ThemeScrollable;
 use iced::theme::TextInput as ThemeTextInput;
 use iced::widget::scrollable::StyleSheet as ThemeScrollableStyleSheet;
-use iced::{Background, BorderRadius, Color};
+use iced::{Background, BorderRadius, Color, Theme};
+use iced_aw::style::card::{Appearance as CardAppearance, StyleSheet as CardStyleSheet};
+use iced_aw::style::CardStyles;
 use iced_style::menu::{Appearance as MenuAppearance, StyleSheet as MenuStyleSheet};
+use serde::{Deserialize, Serialize};
 use std::rc::Rc;
 
 use iced::widget::button::{Appearance as ButtonAppearance, StyleSheet as ButtonStyleSheet};
@@ -19,9 +22,108 @@ use iced::widget::text_input::{
     Appearance as TextInputAppearance, StyleSheet as TextInputStyleSheet,
 };
 
-#[derive(Debug, Clone)]
-enum ThemeType {
-    Custom,
+/// A named set of colors the stylesheets below are derived from.
+///
+/// Colors are stored as `#RRGGBB` (or `#RRGGBBAA`) strings so user themes can
+/// be written by hand in the `themes` folder of the data directory.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThemeDefinition {
+    pub name: String,
+    pub background: String,
+    pub text: String,
+    pub accent: String,
+    #[serde(default = "default_success")]
+    pub success: String,
+    #[serde(default = "default_danger")]
+    pub danger: String,
+}
+
+fn default_success() -> String {
+    "#12664F".to_string()
+}
+
+fn default_danger() -> String {
+    "#C3423F".to_string()
+}
+
+impl ThemeDefinition {
+    fn builtin(name: &str, background: &str, text: &str, accent: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            background: background.to_string(),
+            text: text.to_string(),
+            accent: accent.to_string(),
+            success: default_success(),
+            danger: default_danger(),
+        }
+    }
+
+    pub fn palette(&self) -> Result<IcedPalette, String> {
+        Ok(IcedPalette {
+            background: parse_hex(&self.background)?,
+            text: parse_hex(&self.text)?,
+            primary: parse_hex(&self.accent)?,
+            success: parse_hex(&self.success)?,
+            danger: parse_hex(&self.danger)?,
+        })
+    }
+
+    pub fn theme(&self) -> Theme {
+        match self.palette() {
+            Ok(palette) => Theme::custom(palette),
+            Err(_) => Theme::Dark,
+        }
+    }
+}
+
+pub fn builtin_themes() -> Vec<ThemeDefinition> {
+    vec![
+        ThemeDefinition::builtin("Dark", "#1F1F1F", "#CCCCCC", "#BB28AF"),
+        ThemeDefinition::builtin("Light", "#F2F2F2", "#1E1E1E", "#9A1F90"),
+        ThemeDefinition {
+            success: "#00E000".to_string(),
+            danger: "#FF4040".to_string(),
+            ..ThemeDefinition::builtin("High Contrast", "#000000", "#FFFFFF", "#FFD400")
+        },
+    ]
+}
+
+fn parse_hex(input: &str) -> Result<Color, String> {
+    let hex = input.trim().trim_start_matches('#');
+    let channel = |index: usize| {
+        hex.get(index..index + 2)
+            .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+            .ok_or_else(|| format!("Invalid color: {}", input))
+    };
+
+    match hex.len() {
+        6 => Ok(Color::from_rgb8(channel(0)?, channel(2)?, channel(4)?)),
+        8 => Ok(Color::from_rgba8(
+            channel(0)?,
+            channel(2)?,
+            channel(4)?,
+            f32::from(channel(6)?) / 255.0,
+        )),
+        _ => Err(format!("Invalid color: {}", input)),
+    }
+}
+
+/// Brightens (positive `amount`) or darkens (negative `amount`) a color.
+fn shade(color: Color, amount: f32) -> Color {
+    Color::from_rgba(
+        (color.r + amount).clamp(0.0, 1.0),
+        (color.g + amount).clamp(0.0, 1.0),
+        (color.b + amount).clamp(0.0, 1.0),
+        color.a,
+    )
+}
+
+fn muted(style: &Theme) -> Color {
+    let palette = style.palette();
+    Color {
+        a: 0.6,
+        ..palette.text
+    }
 }
 
 pub fn container_theme() -> ThemeContainer {
@@ -31,6 +133,51 @@ pub fn container_theme() -> ThemeContainer {
 #[derive(Debug, Clone, Copy)]
 struct ContainerTheme;
 
+/// A task row that is part of the multi-selection.
+pub fn selected_container_theme() -> ThemeContainer {
+    ThemeContainer::Custom(
+        Box::new(SelectedContainerTheme) as Box<dyn StyleSheet<Style = iced::Theme>>
+    )
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SelectedContainerTheme;
+
+impl StyleSheet for SelectedContainerTheme {
+    type Style = iced::Theme;
+
+    fn appearance(&self, style: &Self::Style) -> ContainerAppearance {
+        let palette = style.extended_palette();
+        ContainerAppearance {
+            background: Some(Background::Color(palette.primary.weak.color)),
+            text_color: Some(palette.primary.weak.text),
+            ..ContainerTheme.appearance(style)
+        }
+    }
+}
+
+/// The task row that keyboard commands currently act on.
+pub fn focused_container_theme() -> ThemeContainer {
+    ThemeContainer::Custom(
+        Box::new(FocusedContainerTheme) as Box<dyn StyleSheet<Style = iced::Theme>>
+    )
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FocusedContainerTheme;
+
+impl StyleSheet for FocusedContainerTheme {
+    type Style = iced::Theme;
+
+    fn appearance(&self, style: &Self::Style) -> ContainerAppearance {
+        ContainerAppearance {
+            border_width: 1.5,
+            border_color: style.palette().primary,
+            ..ContainerTheme.appearance(style)
+        }
+    }
+}
+
 pub fn scrollable_theme() -> ThemeScrollable {
     ThemeScrollable::Custom(
         Box::new(ScrollableTheme) as Box<dyn ThemeScrollableStyleSheet<Style = iced::Theme>>
@@ -43,24 +190,25 @@ impl ThemeScrollableStyleSheet for ScrollableTheme {
     type Style = iced::Theme;
 
     fn active(&self, style: &Self::Style) -> Scrollbar {
-        let r = 73.3 / 100.0;
-        let g = 15.7 / 100.0;
-        let b = 68.6 / 100.0;
+        let palette = style.extended_palette();
         Scrollbar {
-            background: Some(Background::Color(Color::from_rgb(0.0, 0.0, 0.0))), // Customize the background color of the scrollbar
-            border_radius: BorderRadius::from(5.0), // Customize the border radiux s of the scrollbar
-            border_width: 0.0,                      // Customize the border width of the scrollbar
-            border_color: Color::from_rgb(0.5, 0.5, 0.5), // Customize the border color of the scrollbar
+            background: Some(Background::Color(shade(
+                palette.background.base.color,
+                -0.2,
+            ))),
+            border_radius: BorderRadius::from(5.0),
+            border_width: 0.0,
+            border_color: palette.background.strong.color,
             scroller: Scroller {
-                color: Color::from_rgb(r, g, b),
-                border_radius: BorderRadius::from(5.0), // Customize the border radius of the scroller
-                border_width: 0.0, // Customize the border width of the scroller
-                border_color: Color::from_rgb(0.5, 0.5, 0.5), // Customize the border color of the scroller
+                color: palette.primary.base.color,
+                border_radius: BorderRadius::from(5.0),
+                border_width: 0.0,
+                border_color: palette.background.strong.color,
             },
         }
     }
 
-    fn hovered(&self, style: &Self::Style, is_mouse_over_scrollbar: bool) -> Scrollbar {
+    fn hovered(&self, style: &Self::Style, _is_mouse_over_scrollbar: bool) -> Scrollbar {
         self.active(style)
     }
 }
@@ -69,33 +217,13 @@ impl StyleSheet for ContainerTheme {
     type Style = iced::Theme;
 
     fn appearance(&self, style: &Self::Style) -> ContainerAppearance {
-        let mut appearance = ContainerAppearance {
+        let palette = style.extended_palette();
+        ContainerAppearance {
             border_radius: BorderRadius::from(5.0),
+            background: Some(Background::Color(palette.background.weak.color)),
+            text_color: Some(palette.background.weak.text),
             ..ContainerAppearance::default()
-        };
-
-        let red = 6.7 / 255.0;
-        let green = 6.7 / 255.0;
-        let blue = 6.7 / 255.0;
-
-        #[cfg(target_os = "linux")]
-        {
-            appearance.background = Some(Background::Color(Color::from_rgb(
-                red + 0.2,
-                green + 0.2,
-                blue + 0.2,
-            )));
         }
-
-        #[cfg(target_os = "windows")]
-        {
-            appearance.background = Some(Background::Color(Color::from_rgb(
-                red + 0.1,
-                green + 0.1,
-                blue + 0.1,
-            )));
-        }
-        appearance
     }
 }
 
@@ -110,54 +238,33 @@ impl ButtonStyleSheet for ButtonTheme {
     type Style = iced::Theme;
 
     fn active(&self, style: &Self::Style) -> ButtonAppearance {
-        let r = 73.3 / 100.0;
-        let g = 15.7 / 100.0;
-        let b = 68.6 / 100.0;
-
-        let mut appearance = ButtonAppearance {
+        let primary = style.extended_palette().primary.base;
+        ButtonAppearance {
             border_radius: BorderRadius::from(2.0),
-            background: Some(Background::Color(Color::from_rgb(r, g, b))),
-            text_color: Color::from_rgb(0.8, 0.8, 0.8),
+            background: Some(Background::Color(primary.color)),
+            text_color: primary.text,
             ..ButtonAppearance::default()
-        };
-
-        appearance
+        }
     }
 
     fn hovered(&self, style: &Self::Style) -> ButtonAppearance {
-        let r = 73.3 / 100.0;
-        let g = 15.7 / 100.0;
-        let b = 68.6 / 100.0;
-        let mut appearance = ButtonAppearance {
+        let primary = style.extended_palette().primary.base;
+        ButtonAppearance {
             border_radius: BorderRadius::from(2.0),
-            background: Some(Background::Color(Color::from_rgb(
-                r + 0.2,
-                g + 0.2,
-                b + 0.2,
-            ))),
-            text_color: Color::from_rgb(0.8, 0.8, 0.8),
+            background: Some(Background::Color(shade(primary.color, 0.2))),
+            text_color: primary.text,
             ..ButtonAppearance::default()
-        };
-
-        appearance
+        }
     }
 
     fn pressed(&self, style: &Self::Style) -> ButtonAppearance {
-        let r = 73.3 / 100.0;
-        let g = 15.7 / 100.0;
-        let b = 68.6 / 100.0;
-
-        let mut appearance = ButtonAppearance {
+        let primary = style.extended_palette().primary.base;
+        ButtonAppearance {
             border_radius: BorderRadius::from(2.0),
-            background: Some(Background::Color(Color::from_rgb(
-                r + 0.3,
-                g + 0.3,
-                b + 0.3,
-            ))),
+            background: Some(Background::Color(shade(primary.color, 0.3))),
+            text_color: primary.text,
             ..ButtonAppearance::default()
-        };
-
-        appearance
+        }
     }
 }
 
@@ -172,17 +279,14 @@ impl TextInputStyleSheet for InputTheme {
     type Style = iced::Theme;
 
     fn active(&self, style: &Self::Style) -> TextInputAppearance {
-        let r = 73.3 / 100.0;
-        let g = 15.7 / 100.0;
-        let b = 68.6 / 100.0;
-        let mut appearance = TextInputAppearance {
-            background: Background::Color(Color::from_rgb(0.2, 0.2, 0.2)),
+        let palette = style.extended_palette();
+        TextInputAppearance {
+            background: Background::Color(palette.background.weak.color),
             border_radius: BorderRadius::from(2.0),
-            border_color: Color::from_rgb(r, g, b),
+            border_color: palette.primary.base.color,
             border_width: 0.4,
-            icon_color: Color::from_rgb(0.5, 0.5, 0.5),
-        };
-        appearance
+            icon_color: muted(style),
+        }
     }
 
     fn focused(&self, style: &Self::Style) -> TextInputAppearance {
@@ -194,18 +298,15 @@ impl TextInputStyleSheet for InputTheme {
     }
 
     fn placeholder_color(&self, style: &Self::Style) -> Color {
-        Color::from_rgb(0.5, 0.5, 0.5)
+        muted(style)
     }
 
     fn value_color(&self, style: &Self::Style) -> Color {
-        Color::from_rgb(0.8, 0.8, 0.8)
+        style.palette().text
     }
 
     fn selection_color(&self, style: &Self::Style) -> Color {
-        let r = 73.3 / 100.0;
-        let g = 15.7 / 100.0;
-        let b = 68.6 / 100.0;
-        Color::from_rgb(r, g, b)
+        style.palette().primary
     }
 
     fn disabled(&self, style: &Self::Style) -> TextInputAppearance {
@@ -213,7 +314,7 @@ impl TextInputStyleSheet for InputTheme {
     }
 
     fn disabled_color(&self, style: &Self::Style) -> Color {
-        Color::from_rgb(1.0, 1.0, 1.0)
+        muted(style)
     }
 }
 
@@ -228,34 +329,28 @@ pub fn checkbox_theme() -> ThemeCheckbox {
 
 impl CheckboxStyleSheet for CheckboxTheme {
     type Style = iced::Theme;
-    fn active(&self, style: &Self::Style, is_checked: bool) -> CheckboxAppearance {
-        let r = 73.3 / 100.0;
-        let g = 15.7 / 100.0;
-        let b = 68.6 / 100.0;
-        let mut appearance = CheckboxAppearance {
-            background: Background::Color(Color::from_rgb(0.2, 0.2, 0.2)),
+    fn active(&self, style: &Self::Style, _is_checked: bool) -> CheckboxAppearance {
+        let palette = style.extended_palette();
+        CheckboxAppearance {
+            background: Background::Color(palette.background.weak.color),
             border_radius: BorderRadius::from(2.0),
             border_width: 0.4,
-            border_color: Color::from_rgb(r, g, b),
-            icon_color: Color::from_rgb(r, g, b),
-            text_color: Some(Color::from_rgb(0.8, 0.8, 0.8)),
-        };
-        appearance
+            border_color: palette.primary.base.color,
+            icon_color: palette.primary.base.color,
+            text_color: Some(style.palette().text),
+        }
     }
 
-    fn hovered(&self, style: &Self::Style, is_checked: bool) -> CheckboxAppearance {
-        let r = 73.3 / 100.0;
-        let g = 15.7 / 100.0;
-        let b = 68.6 / 100.0;
-        let mut appearance = CheckboxAppearance {
-            background: Background::Color(Color::from_rgb(r - 0.2, g - 0.2, b - 0.2)),
+    fn hovered(&self, style: &Self::Style, _is_checked: bool) -> CheckboxAppearance {
+        let palette = style.extended_palette();
+        CheckboxAppearance {
+            background: Background::Color(palette.primary.weak.color),
             border_radius: BorderRadius::from(2.0),
             border_width: 0.4,
-            border_color: Color::from_rgb(r, g, b),
-            icon_color: Color::from_rgb(r + 0.1, g + 0.1, b + 0.1),
-            text_color: Some(Color::from_rgb(0.8, 0.8, 0.8)),
-        };
-        appearance
+            border_color: palette.primary.base.color,
+            icon_color: shade(palette.primary.base.color, 0.1),
+            text_color: Some(style.palette().text),
+        }
     }
 }
 
@@ -269,17 +364,15 @@ impl MenuStyleSheet for MenuTheme {
     type Style = iced::Theme;
 
     fn appearance(&self, style: &Self::Style) -> MenuAppearance {
-        let r = 73.3 / 100.0;
-        let g = 15.7 / 100.0;
-        let b = 68.6 / 100.0;
+        let palette = style.extended_palette();
         MenuAppearance {
-            background: Background::Color(Color::from_rgb(0.2, 0.2, 0.2)),
+            background: Background::Color(palette.background.weak.color),
             border_radius: BorderRadius::from(2.0),
             border_width: 0.4,
-            border_color: Color::from_rgb(0.5, 0.5, 0.5),
-            text_color: Color::from_rgb(0.8, 0.8, 0.8),
-            selected_text_color: Color::from_rgb(0.8, 0.8, 0.8),
-            selected_background: Background::Color(Color::from_rgb(r, g, b)),
+            border_color: palette.background.strong.color,
+            text_color: style.palette().text,
+            selected_text_color: palette.primary.base.text,
+            selected_background: Background::Color(palette.primary.base.color),
         }
     }
 }
@@ -293,37 +386,49 @@ pub fn pick_list_theme() -> ThemePickList {
 impl PickListStyleSheet for PickListTheme {
     type Style = iced::Theme;
 
-    fn active(&self, _style: &Self::Style) -> PickListAppearance {
-        let r = 73.3 / 100.0;
-        let g = 15.7 / 100.0;
-        let b = 68.6 / 100.0;
+    fn active(&self, style: &Self::Style) -> PickListAppearance {
+        let palette = style.extended_palette();
         PickListAppearance {
-            background: Background::Color(Color::from_rgb(0.2, 0.2, 0.2)),
+            background: Background::Color(palette.background.weak.color),
             border_radius: BorderRadius::from(2.0),
             border_width: 0.4,
-            border_color: Color::from_rgb(r, g, b),
-            text_color: Color::from_rgb(0.8, 0.8, 0.8),
-            placeholder_color: Color::from_rgb(0.8, 0.8, 0.8),
-            handle_color: Color::from_rgb(r, g, b),
+            border_color: palette.primary.base.color,
+            text_color: style.palette().text,
+            placeholder_color: muted(style),
+            handle_color: palette.primary.base.color,
         }
     }
 
     fn hovered(&self, style: &Self::Style) -> PickListAppearance {
-        let r = 73.3 / 100.0;
-        let g = 15.7 / 100.0;
-        let b = 68.6 / 100.0;
+        let palette = style.extended_palette();
         PickListAppearance {
-            background: Background::Color(Color::from_rgb(0.4, 0.4, 0.4)),
-            border_radius: BorderRadius::from(2.0),
-            border_width: 0.4,
-            border_color: Color::from_rgb(r, g, b),
-            text_color: Color::from_rgb(0.8, 0.8, 0.8),
-            placeholder_color: Color::from_rgb(0.8, 0.8, 0.8),
-            handle_color: Color::from_rgb(r, g, b),
+            background: Background::Color(palette.background.strong.color),
+            ..self.active(style)
         }
     }
 }
 
-// use iced_aw::card::StyleSheet as CardStyleSheet;
-// use iced_aw::card::Appearance as CardAppearance;
-// use iced_aw::style::card as ThemeCard;
+#[derive(Debug, Clone, Copy)]
+struct CardTheme;
+
+pub fn card_theme() -> CardStyles {
+    CardStyles::custom(CardTheme)
+}
+
+impl CardStyleSheet for CardTheme {
+    type Style = iced::Theme;
+
+    fn active(&self, style: &Self::Style) -> CardAppearance {
+        let palette = style.extended_palette();
+        CardAppearance {
+            background: Background::Color(palette.background.base.color),
+            border_color: palette.background.strong.color,
+            head_background: Background::Color(palette.background.strong.color),
+            head_text_color: palette.background.strong.text,
+            body_text_color: style.palette().text,
+            foot_text_color: style.palette().text,
+            close_color: palette.background.strong.text,
+            ..CardAppearance::default()
+        }
+    }
+}
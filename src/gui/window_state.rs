@@ -0,0 +1,297 @@
+use crate::gui::json_handling::Settings;
+use iced::window::Position;
+
+/// How much of the window has to stay on screen so it can still be grabbed.
+const MIN_VISIBLE: i32 = 100;
+
+/// How often moves and resizes are written while the window changes, a
+/// drag reports many of them.
+pub const SAVE_INTERVAL_MILLIS: u64 = 500;
+
+/// Windows parks minimized windows at this position, it must never be saved.
+const MINIMIZED_SENTINEL: i32 = -32000;
+
+/// Where no monitor layout is known, positions past this are taken to be
+/// left over from a screen that is gone. It is wider than most multi-monitor
+/// desktops.
+const MAX_UNCHECKED_COORDINATE: i32 = 8192;
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use winapi::um::winuser::{
+        GetActiveWindow, GetSystemMetrics, IsZoomed, SM_CXVIRTUALSCREEN, SM_CYVIRTUALSCREEN,
+        SM_XVIRTUALSCREEN, SM_YVIRTUALSCREEN,
+    };
+
+    /// The bounding box of all monitors as `(x, y, width, height)`.
+    pub fn virtual_screen() -> Option<(i32, i32, i32, i32)> {
+        let (x, y, width, height) = unsafe {
+            (
+                GetSystemMetrics(SM_XVIRTUALSCREEN),
+                GetSystemMetrics(SM_YVIRTUALSCREEN),
+                GetSystemMetrics(SM_CXVIRTUALSCREEN),
+                GetSystemMetrics(SM_CYVIRTUALSCREEN),
+            )
+        };
+        if width > 0 && height > 0 {
+            Some((x, y, width, height))
+        } else {
+            None
+        }
+    }
+
+    pub fn is_maximized() -> bool {
+        unsafe {
+            let window = GetActiveWindow();
+            !window.is_null() && IsZoomed(window) != 0
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use core_graphics::display::CGDisplay;
+    use objc::runtime::{Object, NO};
+    use objc::{class, msg_send, sel, sel_impl};
+
+    /// The bounding box of all displays as `(x, y, width, height)`, in the
+    /// same top-left based coordinates as window positions.
+    pub fn virtual_screen() -> Option<(i32, i32, i32, i32)> {
+        let bounds: Vec<_> = CGDisplay::active_displays()
+            .ok()?
+            .into_iter()
+            .map(|id| CGDisplay::new(id).bounds())
+            .collect();
+        let left = bounds.iter().map(|rect| rect.origin.x).reduce(f64::min)?;
+        let top = bounds.iter().map(|rect| rect.origin.y).reduce(f64::min)?;
+        let right = bounds
+            .iter()
+            .map(|rect| rect.origin.x + rect.size.width)
+            .reduce(f64::max)?;
+        let bottom = bounds
+            .iter()
+            .map(|rect| rect.origin.y + rect.size.height)
+            .reduce(f64::max)?;
+        Some((
+            left as i32,
+            top as i32,
+            (right - left) as i32,
+            (bottom - top) as i32,
+        ))
+    }
+
+    /// Zoomed is what macOS calls a window that fills the screen without
+    /// going full screen.
+    pub fn is_maximized() -> bool {
+        unsafe {
+            let app: *mut Object = msg_send![class!(NSApplication), sharedApplication];
+            let window: *mut Object = msg_send![app, keyWindow];
+            if window.is_null() {
+                return false;
+            }
+            let zoomed: objc::runtime::BOOL = msg_send![window, isZoomed];
+            zoomed != NO
+        }
+    }
+}
+
+// libX11 is loaded at runtime, without an X server (or on Wayland, where
+// windows cannot be placed anyway) there is no layout and the position is
+// only checked against `MAX_UNCHECKED_COORDINATE`.
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use std::ffi::CString;
+    use std::os::raw::{c_int, c_uchar, c_ulong};
+    use x11_dl::xlib::{self, Xlib};
+
+    /// Runs `query` on a short-lived connection to the X server.
+    fn with_display<T>(query: impl FnOnce(&Xlib, *mut xlib::Display) -> Option<T>) -> Option<T> {
+        let xlib = Xlib::open().ok()?;
+        unsafe {
+            let display = (xlib.XOpenDisplay)(std::ptr::null());
+            if display.is_null() {
+                return None;
+            }
+            let result = query(&xlib, display);
+            (xlib.XCloseDisplay)(display);
+            result
+        }
+    }
+
+    /// The atoms or windows in a 32 bit property of `window`.
+    unsafe fn property(
+        xlib: &Xlib,
+        display: *mut xlib::Display,
+        window: xlib::Window,
+        name: &str,
+    ) -> Vec<c_ulong> {
+        let (mut actual_type, mut format, mut items, mut remaining) = (0, 0 as c_int, 0, 0);
+        let mut data: *mut c_uchar = std::ptr::null_mut();
+        let status = (xlib.XGetWindowProperty)(
+            display,
+            window,
+            atom(xlib, display, name),
+            0,
+            1024,
+            xlib::False,
+            xlib::AnyPropertyType as c_ulong,
+            &mut actual_type,
+            &mut format,
+            &mut items,
+            &mut remaining,
+            &mut data,
+        );
+        if status != c_int::from(xlib::Success) || data.is_null() {
+            return Vec::new();
+        }
+        // 32 bit items are handed out as C longs.
+        let values = if format == 32 {
+            std::slice::from_raw_parts(data as *const c_ulong, items as usize).to_vec()
+        } else {
+            Vec::new()
+        };
+        (xlib.XFree)(data.cast());
+        values
+    }
+
+    unsafe fn atom(xlib: &Xlib, display: *mut xlib::Display, name: &str) -> xlib::Atom {
+        let name = CString::new(name).unwrap_or_default();
+        (xlib.XInternAtom)(display, name.as_ptr(), xlib::False)
+    }
+
+    /// The size of the X screen, which spans all monitors.
+    pub fn virtual_screen() -> Option<(i32, i32, i32, i32)> {
+        with_display(|xlib, display| unsafe {
+            let screen = (xlib.XDefaultScreen)(display);
+            let width = (xlib.XDisplayWidth)(display, screen);
+            let height = (xlib.XDisplayHeight)(display, screen);
+            (width > 0 && height > 0).then_some((0, 0, width, height))
+        })
+    }
+
+    /// Whether the window manager has the active window maximized both ways.
+    pub fn is_maximized() -> bool {
+        with_display(|xlib, display| unsafe {
+            let root = (xlib.XDefaultRootWindow)(display);
+            let window = *property(xlib, display, root, "_NET_ACTIVE_WINDOW").first()?;
+            let state = property(xlib, display, window, "_NET_WM_STATE");
+            let vertical = atom(xlib, display, "_NET_WM_STATE_MAXIMIZED_VERT");
+            let horizontal = atom(xlib, display, "_NET_WM_STATE_MAXIMIZED_HORZ");
+            Some(state.contains(&vertical) && state.contains(&horizontal))
+        })
+        .unwrap_or(false)
+    }
+}
+
+#[cfg(not(any(target_os = "windows", unix)))]
+mod platform {
+    pub fn virtual_screen() -> Option<(i32, i32, i32, i32)> {
+        None
+    }
+
+    pub fn is_maximized() -> bool {
+        false
+    }
+}
+
+pub fn is_maximized() -> bool {
+    platform::is_maximized()
+}
+
+/// Whether a reported position belongs to a minimized window.
+pub fn is_minimized_position(x: i32, y: i32) -> bool {
+    x <= MINIMIZED_SENTINEL || y <= MINIMIZED_SENTINEL
+}
+
+/// The saved window position, pulled back onto the visible screen area.
+/// Without a monitor layout, positions that are far off are dropped and the
+/// window opens centered.
+pub fn restored_position(settings: &Settings) -> Position {
+    clamped_position(settings, platform::virtual_screen())
+}
+
+/// `restored_position` within the screen area `(x, y, width, height)`.
+fn clamped_position(settings: &Settings, screen: Option<(i32, i32, i32, i32)>) -> Position {
+    if is_minimized_position(settings.x, settings.y) {
+        return Position::Centered;
+    }
+
+    let width = settings.width as i32;
+    let (x, y) = match screen {
+        Some((screen_x, screen_y, screen_width, screen_height)) => (
+            settings.x.clamp(
+                screen_x - width + MIN_VISIBLE,
+                screen_x + screen_width - MIN_VISIBLE,
+            ),
+            settings
+                .y
+                .clamp(screen_y, screen_y + screen_height - MIN_VISIBLE),
+        ),
+        None if settings.x > MAX_UNCHECKED_COORDINATE || settings.y > MAX_UNCHECKED_COORDINATE => {
+            return Position::Centered;
+        }
+        None => (settings.x.max(MIN_VISIBLE - width), settings.y.max(0)),
+    };
+
+    Position::Specific(x, y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings_at(x: i32, y: i32) -> Settings {
+        Settings {
+            x,
+            y,
+            ..Settings::default()
+        }
+    }
+
+    #[test]
+    fn far_off_positions_open_centered() {
+        assert!(matches!(
+            clamped_position(&settings_at(20_000, 100), None),
+            Position::Centered
+        ));
+        assert!(matches!(
+            clamped_position(&settings_at(-32000, -32000), None),
+            Position::Centered
+        ));
+    }
+
+    #[test]
+    fn positions_left_of_the_screen_keep_part_of_the_window_visible() {
+        let settings = settings_at(-5000, -10);
+        let width = settings.width as i32;
+        assert!(matches!(
+            clamped_position(&settings, None),
+            Position::Specific(x, 0) if x == MIN_VISIBLE - width
+        ));
+        assert!(matches!(
+            clamped_position(&settings_at(300, 200), None),
+            Position::Specific(300, 200)
+        ));
+    }
+
+    #[test]
+    fn positions_off_the_screen_are_pulled_back_onto_it() {
+        // Two 1920x1080 monitors side by side, the window was last on a
+        // third one to the right that has been unplugged.
+        let screen = Some((0, 0, 3840, 1080));
+        let settings = settings_at(5000, 1500);
+        let width = settings.width as i32;
+        assert!(matches!(
+            clamped_position(&settings, screen),
+            Position::Specific(x, y) if x == 3840 - MIN_VISIBLE && y == 1080 - MIN_VISIBLE
+        ));
+        assert!(matches!(
+            clamped_position(&settings_at(-3000, -50), screen),
+            Position::Specific(x, 0) if x == MIN_VISIBLE - width
+        ));
+        assert!(matches!(
+            clamped_position(&settings_at(2000, 300), screen),
+            Position::Specific(2000, 300)
+        ));
+    }
+}
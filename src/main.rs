@@ -1,11 +1,8 @@
 mod gui;
 
-use dirs::home_dir;
 use iced::{window, Application, Settings};
-use std::path::PathBuf;
-use std::process::exit;
 
-use crate::gui::json_handling;
+use crate::gui::{json_handling, window_state};
 
 #[cfg(target_os = "windows")]
 mod windows {
@@ -40,22 +37,19 @@ fn main() -> iced::Result {
 
     let icon = from_file(icon_path).unwrap();
 
-    let window_position: json_handling::WindowPosition = match json_handling::read_settings() {
-        Ok(settings) => json_handling::WindowPosition {
-            x: settings.x,
-            y: settings.y,
-        },
-        Err(_) => json_handling::WindowPosition { x: 100, y: 100 },
-    };
+    let preferences = json_handling::read_settings().unwrap_or_default();
 
     hide_console();
     let settings = Settings {
         window: window::Settings {
-            size: (800, 335),
+            size: (preferences.width, preferences.height),
+            min_size: Some((400, 200)),
             icon: Some(icon),
-            position: (window::Position::Specific(window_position.x, window_position.y)),
+            position: window_state::restored_position(&preferences),
             ..Default::default()
         },
+        default_text_size: f32::from(preferences.font_size),
+        exit_on_close_request: false,
         ..Default::default()
     };
 